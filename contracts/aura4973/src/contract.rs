@@ -1,34 +1,80 @@
 #[cfg(not(feature = "library"))]
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdResult, Binary, to_binary, Deps, Order, entry_point};
+use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, Response, StdError, StdResult, Binary, to_json_binary, Deps, Order, entry_point, HexBinary, Empty, Storage, Addr, BankMsg, Coin, SubMsg, Timestamp};
 
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use cw_utils::Expiration;
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, QueryMsg, InstantiateMsg};
-use crate::state::{Aura4973, ContractInfoResponse, NumNftsResponse, NftInfo, OwnerOfResponse};
+use crate::event::{burn_event, nft_event};
+use crate::hook::{AbtEvent, AbtHookMsg};
+use crate::receiver::AbtReceiveMsg;
+use crate::msg::{BadgeClassMsg, CampaignMsg, ClaimProof, ExecuteMsg, QueryMsg, InstantiateMsg, MigrateMsg, MintAndNotifyMsg, MintMsg, OfferMsg, Requirement, RevokeIssuedByMsg, UpdateContractInfoMsg};
+use crate::state::{increment_count, uri_scheme, AdminResponse, Aura4973, BadgeClass, BalanceResponse, Campaign, ContractInfoResponse, GuardianSet, HasBadgeResponse, HistoryEntry, HoldsResponse, IsClaimedResponse, MintFeeResponse, NftCounts, NftInfoEntry, NftStatus, NumHoldersResponse, Offer, PendingRecovery, ReceivePolicy, RecoveryConfig, RevokeIssuedByResponse, SocialRecovery, SocialRecoveryApprovals, Tombstone, StatsResponse, NumNftsResponse, NftInfo, OwnerOfResponse, UriConfig, VerifyMetadataResponse};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:aura-4973";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// length in bytes of a sha256 digest
+const SHA256_LENGTH: usize = 32;
+
+// longest duration in seconds that can be configured, keeps every deadline
+// computed from it far from the end of the Timestamp range
+const MAX_DURATION: u64 = 100 * 365 * 24 * 60 * 60;
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+// settings for the pages of bulk revocations
+const MAX_REVOKE_LIMIT: u32 = 100;
+const DEFAULT_REVOKE_LIMIT: u32 = 30;
+
+// maximum number of nodes of a requirement of the Holds query
+const MAX_REQUIREMENT_NODES: usize = 30;
+
+// settings for the pages of reindexing after a migration
+const MAX_REINDEX_LIMIT: u32 = 100;
+const DEFAULT_REINDEX_LIMIT: u32 = 30;
+
+// number of nfts moved by each page of a recovery
+const REBIND_LIMIT: usize = 30;
+
+// metadata of a new nft, checked against the uri rules and its badge class
+pub struct NftMetadata {
+    pub class_id: Option<String>,
+    pub nft_uri: String,
+    pub base_uri_version: Option<u64>,
+    pub metadata_hash: Option<HexBinary>,
+    pub kind: Option<String>,
+}
+
+// a page of nfts moved by a recovery
+pub struct Rebind {
+    // events of the moved nfts
+    pub events: Vec<Event>,
+    // messages notifying the hooks of the moved nfts
+    pub hooks: Vec<SubMsg>,
+    // ids of the nfts left with the old owner because the new owner already holds their unique kind
+    pub skipped: Vec<String>,
+    // id of the last nft of the page if more nfts may follow
+    pub next: Option<String>,
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     // get default Aura4973 contract
     let contract = Aura4973::default();
 
-    // call the instantiate function
-    let response = contract
-        .instantiate(deps, msg)
-        .unwrap();
-        
-    // return the response
-    Ok(response)
-
+    // call and return the instantiate function
+    contract.instantiate(deps, msg)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -54,13 +100,21 @@ pub fn query(
     // get default Aura4973 contract
     let contract = Aura4973::default();
 
-    // call the query function
-    let response = contract
-        .query(deps, _env, msg)
-        .unwrap();
+    // call and return the query function
+    contract.query(deps, _env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(
+    deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+) -> Result<Response, ContractError> {
+    // get default Aura4973 contract
+    let contract = Aura4973::default();
 
-    // return the response
-    Ok(to_binary(&response)?)
+    // call and return the migrate function
+    contract.migrate(deps, env, msg)
 }
 
 impl<'a> Aura4973<'a>{
@@ -68,18 +122,49 @@ impl<'a> Aura4973<'a>{
         &self,
         deps: DepsMut,
         msg: InstantiateMsg,
-    ) -> StdResult<Response> {
+    ) -> Result<Response, ContractError> {
         set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
         let info = ContractInfoResponse {
             name: msg.name,
             symbol: msg.symbol,
+            description: None,
+            image: None,
+            external_url: None,
+            issuer_name: None,
+            metadata_uri: None,
         };
         
         let minter = deps.api.addr_validate(&msg.minter)?;
 
+        // the minter administers the contract unless an admin is given
+        let admin = match msg.admin {
+            Some(admin) => deps.api.addr_validate(&admin)?,
+            None => minter.clone(),
+        };
+
+        let uri_config = msg.uri_config.unwrap_or_default();
+        validate_uri_config(&uri_config)?;
+
+        let recovery_config = msg.recovery_config.unwrap_or_default();
+        validate_recovery_config(&recovery_config)?;
+
+        // fees are withdrawn to the admin unless a treasury is given
+        let treasury = match msg.treasury {
+            Some(treasury) => deps.api.addr_validate(&treasury)?,
+            None => admin.clone(),
+        };
+        validate_mint_fee(&msg.mint_fee)?;
+
         self.contract_info.save(deps.storage, &info)?;
         self.minter.save(deps.storage, &minter)?;
+        self.admin.save(deps.storage, &admin)?;
+        self.save_uri_config(deps.storage, &uri_config)?;
+        self.recovery_config.save(deps.storage, &recovery_config)?;
+        self.treasury.save(deps.storage, &treasury)?;
+        if let Some(mint_fee) = msg.mint_fee {
+            self.mint_fee.save(deps.storage, &mint_fee)?;
+        }
         self.nft_count.save(deps.storage, &0u64)?;
 
         // set the contract version
@@ -88,6 +173,97 @@ impl<'a> Aura4973<'a>{
         Ok(Response::default())
     }
 
+    // migrate brings the storage of an older version of the contract up to date:
+    // it fills the settings added since with their defaults and rebuilds the nft indexes and counters
+    pub fn migrate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        msg: MigrateMsg,
+    ) -> Result<Response, ContractError> {
+        let version = get_contract_version(deps.storage)?;
+        if version.contract != CONTRACT_NAME {
+            return Err(ContractError::CannotMigrate { previous_contract: version.contract });
+        }
+
+        // the migration may name an admin, otherwise the minter administers the contract
+        // as when no admin is given at instantiation
+        if let Some(admin) = msg.admin {
+            let admin = deps.api.addr_validate(&admin)?;
+            self.admin.save(deps.storage, &admin)?;
+            self.pending_admin.remove(deps.storage);
+        } else if self.admin.may_load(deps.storage)?.is_none() {
+            let minter = self.minter.load(deps.storage)?;
+            self.admin.save(deps.storage, &minter)?;
+        }
+        if self.treasury.may_load(deps.storage)?.is_none() {
+            let admin = self.admin.load(deps.storage)?;
+            self.treasury.save(deps.storage, &admin)?;
+        }
+        if self.uri_config.may_load(deps.storage)?.is_none() {
+            self.uri_config.save(deps.storage, &UriConfig::default())?;
+        }
+        if self.recovery_config.may_load(deps.storage)?.is_none() {
+            self.recovery_config.save(deps.storage, &RecoveryConfig::default())?;
+        }
+
+        // nfts minted before the counters existed are reindexed in pages, the first one right away.
+        // The next attribute is only set while nfts remain to be reindexed
+        if self.nft_counts.may_load(deps.storage)?.is_none() && self.reindex.may_load(deps.storage)?.is_none() {
+            self.nft_counts.save(deps.storage, &NftCounts::default())?;
+            self.num_holders.save(deps.storage, &0)?;
+            self.reindex.save(deps.storage, &None)?;
+        }
+        let (reindexed, next) = match self.reindex.may_load(deps.storage)? {
+            Some(_) => self.reindex_nfts(deps.storage, &env, DEFAULT_REINDEX_LIMIT as usize)?,
+            None => (0, None),
+        };
+
+        set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", version.version)
+            .add_attribute("to_version", CONTRACT_VERSION)
+            .add_attribute("reindexed", reindexed.to_string())
+            .add_attributes(next.map(|next| ("next", next))))
+    }
+
+    // reindex_nfts saves the next page of nfts again, which writes their entries in the indexes and
+    // counters added since they were minted. Returns the number of reindexed nfts and, if the reindex
+    // is not finished, the id of the last one
+    fn reindex_nfts(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        limit: usize,
+    ) -> Result<(usize, Option<String>), ContractError> {
+        let start_after = self.reindex.load(storage)?;
+        let nft_ids = self.nfts
+            .keys(storage, start_after.as_deref().map(Bound::exclusive), None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let minter = self.minter.load(storage)?;
+        for nft_id in &nft_ids {
+            let mut nft_info = self.nfts.load(storage, nft_id)?;
+            // only the minter could mint before the issuer was recorded
+            if nft_info.issuer.is_empty() {
+                nft_info.issuer = minter.to_string();
+            }
+            self.nfts.save(storage, nft_id, &nft_info, env.block.height)?;
+            self.update_counts(storage, &nft_info, true, env.block.height)?;
+        }
+
+        // the reindex is finished once a page is not full
+        let next = if nft_ids.len() == limit { nft_ids.last().cloned() } else { None };
+        match &next {
+            Some(next) => self.reindex.save(storage, &Some(next.clone()))?,
+            None => self.reindex.remove(storage),
+        }
+        Ok((nft_ids.len(), next))
+    }
+
     pub fn execute(
         &self,
         deps: DepsMut,
@@ -95,72 +271,549 @@ impl<'a> Aura4973<'a>{
         info: MessageInfo,
         msg: ExecuteMsg,
     ) -> Result<Response, ContractError> {
+        // the counters are incomplete until every nft is reindexed after a migration
+        if !matches!(msg, ExecuteMsg::Reindex { .. }) && self.reindex.may_load(deps.storage)?.is_some() {
+            return Err(ContractError::ReindexPending {});
+        }
+
         match msg {
-            ExecuteMsg::Mint { nft_id, owner, nft_uri } => self.execute_mint(deps, env, info, nft_id, owner, nft_uri),
+            ExecuteMsg::Mint(msg) => self.execute_mint(deps, env, info, msg),
+            ExecuteMsg::MintAndNotify(msg) => self.execute_mint_and_notify(deps, env, info, msg),
             ExecuteMsg::UnEquip { nft_id } => self.execute_unequip(deps, env, info, nft_id),
             ExecuteMsg::Equip { nft_id } => self.execute_equip(deps, env, info, nft_id),
             ExecuteMsg::UnAdmit { nft_id } => self.execute_unadmit(deps, env, info, nft_id),
+            ExecuteMsg::Destroy { nft_id } => self.execute_destroy(deps, env, info, nft_id),
+            ExecuteMsg::RevokeIssuedBy(msg) => self.execute_revoke_issued_by(deps, env, info, msg),
+            ExecuteMsg::Offer(msg) => self.execute_offer(deps, env, info, msg),
+            ExecuteMsg::Accept { nft_id } => self.execute_accept(deps, env, info, nft_id),
+            ExecuteMsg::Reject { nft_id } => self.execute_reject(deps, env, info, nft_id),
+            ExecuteMsg::CancelOffer { nft_id } => self.execute_cancel_offer(deps, env, info, nft_id),
+            ExecuteMsg::CreateCampaign(msg) => self.execute_create_campaign(deps, env, info, msg),
+            ExecuteMsg::Claim { campaign_id, proof } => self.execute_claim(deps, env, info, campaign_id, proof),
+            ExecuteMsg::Recover { from, to } => self.execute_recover(deps, env, info, from, to),
+            ExecuteMsg::ConfirmRecovery { from } => self.execute_confirm_recovery(deps, env, info, from),
+            ExecuteMsg::FinalizeRecovery { from } => self.execute_finalize_recovery(deps, env, info, from),
+            ExecuteMsg::CancelRecovery { from } => self.execute_cancel_recovery(deps, env, info, from),
+            ExecuteMsg::UpdateRecoveryConfig { config } => self.execute_update_recovery_config(deps, env, info, config),
+            ExecuteMsg::SetGuardians { guardians, threshold } => self.execute_set_guardians(deps, env, info, guardians, threshold),
+            ExecuteMsg::ApproveSocialRecovery { owner, new_owner } => self.execute_approve_social_recovery(deps, env, info, owner, new_owner),
+            ExecuteMsg::WithdrawSocialRecoveryApproval { owner } => self.execute_withdraw_social_recovery_approval(deps, env, info, owner),
+            ExecuteMsg::ExecuteSocialRecovery { owner } => self.execute_social_recovery(deps, env, info, owner),
+            ExecuteMsg::CancelSocialRecovery {} => self.execute_cancel_social_recovery(deps, env, info),
+            ExecuteMsg::SetReceivePolicy { policy } => self.execute_set_receive_policy(deps, env, info, policy),
+            ExecuteMsg::TransferNft { .. } => Err(ContractError::Soulbound { action: "transfer_nft".to_string() }),
+            ExecuteMsg::SendNft { .. } => Err(ContractError::Soulbound { action: "send_nft".to_string() }),
+            ExecuteMsg::Approve { .. } => Err(ContractError::Soulbound { action: "approve".to_string() }),
+            ExecuteMsg::Revoke { .. } => Err(ContractError::Soulbound { action: "revoke".to_string() }),
+            ExecuteMsg::ApproveAll { .. } => Err(ContractError::Soulbound { action: "approve_all".to_string() }),
+            ExecuteMsg::RevokeAll { .. } => Err(ContractError::Soulbound { action: "revoke_all".to_string() }),
+            ExecuteMsg::UpdateMintFee { fee, treasury } => self.execute_update_mint_fee(deps, env, info, fee, treasury),
+            ExecuteMsg::Reindex { limit } => self.execute_reindex(deps, env, info, limit),
+            ExecuteMsg::Withdraw { amount, to } => self.execute_withdraw(deps, env, info, amount, to),
+            ExecuteMsg::AddHook { addr } => self.execute_add_hook(deps, env, info, addr),
+            ExecuteMsg::RemoveHook { addr } => self.execute_remove_hook(deps, env, info, addr),
+            ExecuteMsg::UpdateUriConfig { config } => self.execute_update_uri_config(deps, env, info, config),
+            ExecuteMsg::CreateBadgeClass(msg) => self.execute_create_badge_class(deps, env, info, msg),
+            ExecuteMsg::UpdateBadgeClassIssuers { class_id, add, remove } => self.execute_update_badge_class_issuers(deps, env, info, class_id, add, remove),
+            ExecuteMsg::UpdateMinter { minter } => self.execute_update_minter(deps, env, info, minter),
+            ExecuteMsg::ProposeAdmin { admin } => self.execute_propose_admin(deps, env, info, admin),
+            ExecuteMsg::AcceptAdmin {} => self.execute_accept_admin(deps, env, info),
+            ExecuteMsg::UpdateUniqueKinds { add, remove } => self.execute_update_unique_kinds(deps, env, info, add, remove),
+            ExecuteMsg::UpdateContractInfo(msg) => self.execute_update_contract_info(deps, env, info, msg),
         }
     }
 
     pub fn query(
         &self,
         deps: Deps,
-        _env: Env,
+        env: Env,
         msg: QueryMsg,
     ) -> StdResult<Binary> {
         match msg {
-            QueryMsg::ContractInfo {} => to_binary(&self.contract_info(deps)?),
-            QueryMsg::NftInfo { nft_id } => to_binary(&self.nft_info(deps, nft_id)?),
-            QueryMsg::OwnerOf { nft_id } => to_binary(&self.owner_of(deps, nft_id)?),
-            QueryMsg::NumNfts {} => to_binary(&self.num_nfts(deps)?),
-            QueryMsg::AllUnequippedNftOf { owner} => to_binary(&self.all_unequipped_nft_of(deps, owner)?),
-            QueryMsg::AllEquippedNftOf { owner } => to_binary(&self.all_equipped_nft_of(deps, owner)?),
+            QueryMsg::ContractInfo {} => to_json_binary(&self.contract_info(deps)?),
+            QueryMsg::Admin {} => to_json_binary(&self.admin(deps)?),
+            QueryMsg::UriConfig {} => to_json_binary(&self.uri_config.load(deps.storage)?),
+            QueryMsg::NftInfo { nft_id } => to_json_binary(&self.nft_info(deps, nft_id)?),
+            QueryMsg::NftHistory { nft_id, start_after, limit } => to_json_binary(&self.nft_history(deps, nft_id, start_after, limit)?),
+            QueryMsg::Tombstone { nft_id } => to_json_binary(&self.tombstones.may_load(deps.storage, &nft_id)?),
+            QueryMsg::NftInfoAtHeight { nft_id, height } => to_json_binary(&self.nft_info_at_height(deps, nft_id, height)?),
+            QueryMsg::NftInfos { nft_ids } => to_json_binary(&self.nft_infos(deps, nft_ids)?),
+            QueryMsg::OwnerOf { nft_id } => to_json_binary(&self.owner_of(deps, nft_id)?),
+            QueryMsg::MintFee {} => to_json_binary(&self.mint_fee(deps)?),
+            QueryMsg::Hooks {} => to_json_binary(&self.hooks.query_hooks(deps)?),
+            QueryMsg::NumNfts {} => to_json_binary(&self.num_nfts(deps)?),
+            QueryMsg::Stats {} => to_json_binary(&self.stats(deps)?),
+            QueryMsg::BalanceOf { owner } => to_json_binary(&self.balance_of(deps, owner)?),
+            QueryMsg::BalanceAtHeight { owner, height } => to_json_binary(&self.balance_at_height(deps, owner, height)?),
+            QueryMsg::Holders { start_after, limit } => to_json_binary(&self.holders(deps, start_after, limit)?),
+            QueryMsg::NumHolders {} => to_json_binary(&NumHoldersResponse {
+                count: self.num_holders.may_load(deps.storage)?.unwrap_or_default(),
+            }),
+            QueryMsg::AllUnequippedNftOf { owner} => to_json_binary(&self.all_unequipped_nft_of(deps, owner)?),
+            QueryMsg::AllEquippedNftOf { owner } => to_json_binary(&self.all_equipped_nft_of(deps, owner)?),
+            QueryMsg::BadgeClass { class_id } => to_json_binary(&self.resolve_class(deps.storage, self.badge_classes.load(deps.storage, &class_id)?)?),
+            QueryMsg::BadgeClasses { start_after, limit } => to_json_binary(&self.badge_classes(deps, start_after, limit)?),
+            QueryMsg::NftsOfClass { class_id, start_after, limit } => to_json_binary(&self.nfts_of_class(deps, class_id, start_after, limit)?),
+            QueryMsg::TokensByIssuer { issuer, start_after, limit } => to_json_binary(&self.tokens_by_issuer(deps, issuer, start_after, limit)?),
+            QueryMsg::UniqueKinds {} => to_json_binary(&self.unique_kinds(deps)?),
+            QueryMsg::Holds { owner, requirement } => to_json_binary(&self.holds(deps, env, owner, requirement)?),
+            QueryMsg::HasBadge { owner, kind } => to_json_binary(&HasBadgeResponse { has_badge: self.has_badge(deps.storage, &owner, &kind, env.block.time)? }),
+            QueryMsg::Offer { nft_id } => to_json_binary(&self.resolve_offer(deps.storage, self.offers.load(deps.storage, &nft_id)?)?),
+            QueryMsg::OffersByRecipient { recipient, start_after, limit } => to_json_binary(&self.offers_by_recipient(deps, recipient, start_after, limit)?),
+            QueryMsg::Campaign { campaign_id } => to_json_binary(&self.resolve_campaign(deps.storage, self.campaigns.load(deps.storage, &campaign_id)?)?),
+            QueryMsg::IsClaimed { campaign_id, claim_key } => to_json_binary(&IsClaimedResponse { is_claimed: self.claims.has(deps.storage, (&campaign_id, &claim_key)) }),
+            QueryMsg::RecoveryConfig {} => to_json_binary(&self.recovery_config.load(deps.storage)?),
+            QueryMsg::PendingRecovery { from } => to_json_binary(&self.pending_recovery(deps, from)?),
+            QueryMsg::Guardians { owner } => to_json_binary(&self.guardian_set(deps, owner)?),
+            QueryMsg::PendingSocialRecovery { owner } => to_json_binary(&self.pending_social_recovery(deps, owner)?),
+            QueryMsg::SocialRecoveryApprovals { owner } => to_json_binary(&self.social_recovery_approvals(deps, owner)?),
+            QueryMsg::ReceivePolicy { owner } => to_json_binary(&self.receive_policy(deps, owner)?),
+            QueryMsg::VerifyMetadata { nft_id, content_hash } => to_json_binary(&self.verify_metadata(deps, nft_id, content_hash)?),
         }
     }
 
-    // execute_mint is a function that allows the minter mints a nft with id and nft_uri to owner
+    // execute_mint is a function that allows the minter mints a nft with id and nft_uri to owner.
+    // Nfts of a badge class may also be minted by the issuers of the class and inherit its metadata
     pub fn execute_mint(
         &self,
-        deps: DepsMut,
-        _env: Env,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: MintMsg,
+    ) -> Result<Response, ContractError> {
+        // the owner must accept nfts pushed by the sender
+        let owner_addr = deps.api.addr_validate(&msg.owner)?;
+        self.check_receive_policy(deps.storage, &owner_addr, &info.sender, false)?;
+        self.check_not_reserved(deps.storage, &msg.nft_id)?;
+
+        // the sender pays the mint fee
+        let refund = self.collect_fee(deps.storage, &info)?;
+
+        let nft_info = self.mint_nft(deps.branch(), &env, &info.sender, msg)?;
+        let hooks = self.prepare_hooks(deps.storage, AbtEvent::Mint, &nft_info)?;
+
+        Ok(Response::new()
+            .add_messages(refund)
+            .add_submessages(hooks)
+            .add_event(self.transition(deps.storage, &env, "mint", &nft_info, &info.sender, None)?)
+            .add_attribute("action", "mint")
+            .add_attribute("minter", info.sender)
+            .add_attribute("nft_id", nft_info.id)
+            .add_attribute("owner", nft_info.owner))
+    }
+
+    // execute_mint_and_notify is a function that mints a nft to a contract and calls its ReceiveAbt entry
+    pub fn execute_mint_and_notify(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
         info: MessageInfo,
+        msg: MintAndNotifyMsg,
+    ) -> Result<Response, ContractError> {
+        let MintAndNotifyMsg { nft_id, owner, nft_uri, metadata_hash, class_id, kind, msg } = msg;
+
+        // the owner must accept nfts pushed by the sender
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        self.check_receive_policy(deps.storage, &owner_addr, &info.sender, false)?;
+        self.check_not_reserved(deps.storage, &nft_id)?;
+
+        // the sender pays the mint fee
+        let refund = self.collect_fee(deps.storage, &info)?;
+
+        let mint_msg = MintMsg { nft_id, owner, nft_uri, metadata_hash, class_id, kind };
+        let nft_info = self.mint_nft(deps.branch(), &env, &info.sender, mint_msg)?;
+        let hooks = self.prepare_hooks(deps.storage, AbtEvent::Mint, &nft_info)?;
+
+        // tell the owner contract it received the nft
+        let receive = AbtReceiveMsg {
+            sender: info.sender.to_string(),
+            nft_id: nft_info.id.clone(),
+            msg,
+        }
+        .into_cosmos_msg(&nft_info.owner)?;
+
+        Ok(Response::new()
+            .add_messages(refund)
+            .add_message(receive)
+            .add_submessages(hooks)
+            .add_event(self.transition(deps.storage, &env, "mint", &nft_info, &info.sender, None)?)
+            .add_attribute("action", "mint_and_notify")
+            .add_attribute("minter", info.sender)
+            .add_attribute("nft_id", nft_info.id)
+            .add_attribute("owner", nft_info.owner))
+    }
+
+    // mint_nft checks the metadata of a new nft, then mints it like mint_checked_nft does
+    pub fn mint_nft(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        issuer: &Addr,
+        msg: MintMsg,
+    ) -> Result<NftInfo, ContractError> {
+        let MintMsg { nft_id, owner, nft_uri, metadata_hash, class_id, kind } = msg;
+        let metadata = self.check_metadata(deps.storage, class_id, nft_uri, metadata_hash, kind)?;
+        self.mint_checked_nft(deps, env, issuer, nft_id, owner, metadata)
+    }
+
+    // check_metadata checks the metadata given for a new nft and returns the one it is stored with.
+    // Badges of a class carry the metadata and the kind of the class
+    pub fn check_metadata(
+        &self,
+        storage: &dyn Storage,
+        class_id: Option<String>,
+        nft_uri: Option<String>,
+        metadata_hash: Option<HexBinary>,
+        kind: Option<String>,
+    ) -> Result<NftMetadata, ContractError> {
+        let class = match &class_id {
+            Some(class_id) => Some(self.badge_classes.load(storage, class_id)?),
+            None => None,
+        };
+        let uri_config = self.uri_config.load(storage)?;
+        let kind = nft_kind(class.as_ref(), kind)?;
+
+        let class = match class {
+            Some(class) => class,
+            None => {
+                // the metadata hash must be a sha256 digest
+                validate_metadata_hash(&metadata_hash)?;
+
+                let nft_uri = nft_uri.ok_or(ContractError::MissingNftUri {})?;
+                let (nft_uri, base_uri_version) = self.stored_uri(storage, &uri_config, nft_uri)?;
+                return Ok(NftMetadata { class_id, nft_uri, base_uri_version, metadata_hash, kind });
+            }
+        };
+
+        // badges of a class are stored with the metadata of the class, the given one must be absent or the same
+        if let Some(nft_uri) = nft_uri {
+            validate_nft_uri(&uri_config, &nft_uri)?;
+            if uri_config.resolve(&nft_uri) != self.resolve_uri(storage, class.nft_uri.clone(), class.base_uri_version)? {
+                return Err(ContractError::BadgeClassMetadataMismatch { class_id: class.id });
+            }
+        }
+        if metadata_hash.is_some_and(|metadata_hash| class.metadata_hash.as_ref() != Some(&metadata_hash)) {
+            return Err(ContractError::BadgeClassMetadataMismatch { class_id: class.id });
+        }
+        Ok(NftMetadata {
+            class_id,
+            nft_uri: class.nft_uri,
+            base_uri_version: class.base_uri_version,
+            metadata_hash: class.metadata_hash,
+            kind,
+        })
+    }
+
+    // stored_uri checks a nft uri against the configured rules and returns it as it is stored.
+    // Relative uris are kept with the version of the base uri they are relative to,
+    // so that a later change of the base uri does not point the nft elsewhere
+    fn stored_uri(
+        &self,
+        storage: &dyn Storage,
+        config: &UriConfig,
+        nft_uri: String,
+    ) -> Result<(String, Option<u64>), ContractError> {
+        validate_nft_uri(config, &nft_uri)?;
+        let base_uri_version = match uri_scheme(&nft_uri) {
+            Some(_) => None,
+            None => self.base_uri_version(storage)?,
+        };
+        Ok((nft_uri, base_uri_version))
+    }
+
+    // resolve_uri returns the full uri of a stored nft uri, prefixing a relative path
+    // with the version of the base uri it was stored with
+    pub fn resolve_uri(
+        &self,
+        storage: &dyn Storage,
+        nft_uri: String,
+        base_uri_version: Option<u64>,
+    ) -> StdResult<String> {
+        match base_uri_version {
+            Some(version) => Ok(format!("{}{}", self.base_uris.load(storage, version)?, nft_uri)),
+            None => Ok(nft_uri),
+        }
+    }
+
+    // base_uri_version returns the version of the current base uri, if one was ever set
+    fn base_uri_version(
+        &self,
+        storage: &dyn Storage,
+    ) -> StdResult<Option<u64>> {
+        self.base_uris
+            .keys(storage, None, None, Order::Descending)
+            .next()
+            .transpose()
+    }
+
+    // save_uri_config saves the rules for nft uris, keeping a new version of the base uri if it changed.
+    // Versions of the base uri are never changed, relative uris of nfts refer to them
+    fn save_uri_config(
+        &self,
+        storage: &mut dyn Storage,
+        config: &UriConfig,
+    ) -> StdResult<()> {
+        if let Some(base_uri) = &config.base_uri {
+            let version = self.base_uri_version(storage)?;
+            let current = version.map(|version| self.base_uris.load(storage, version)).transpose()?;
+            if current.as_ref() != Some(base_uri) {
+                let version = match version {
+                    Some(version) => increment_count(version)?,
+                    None => 0,
+                };
+                self.base_uris.save(storage, version, base_uri)?;
+            }
+        }
+        self.uri_config.save(storage, config)
+    }
+
+    // mint_checked_nft checks that the issuer may mint the nft, then stores it with metadata
+    // checked by check_metadata and returns its info. Every way of creating a nft goes through this function
+    pub fn mint_checked_nft(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        issuer: &Addr,
         nft_id: String,
         owner: String,
-        nft_uri: String,
-    ) -> Result<Response, ContractError> {
-        let minter = self.minter.load(deps.storage)?;
-        if info.sender != minter {
-            return Err(ContractError::NotMinter {});
+        metadata: NftMetadata,
+    ) -> Result<NftInfo, ContractError> {
+        let NftMetadata { class_id, nft_uri, base_uri_version, metadata_hash, kind } = metadata;
+        self.check_issuer(deps.storage, issuer, &class_id)?;
+
+        // nft ids are never reused
+        if self.nfts.key(&nft_id).has(deps.storage) {
+            return Err(ContractError::NftAlreadyExists { nft_id });
+        }
+        if self.tombstones.has(deps.storage, &nft_id) {
+            return Err(ContractError::NftDestroyed { nft_id });
         }
+        self.check_not_offered(deps.storage, env, &nft_id)?;
+
+        // load the badge class and count the new nft against its supply
+        let class = match &class_id {
+            Some(class_id) => {
+                let mut class = self.badge_classes.load(deps.storage, class_id)?;
+                if class.max_supply.is_some_and(|max_supply| class.minted >= max_supply) {
+                    return Err(ContractError::BadgeClassSupplyExhausted { class_id: class_id.clone() });
+                }
+                class.minted += 1;
+                Some(class)
+            }
+            None => None,
+        };
 
         let owner_addr = deps.api.addr_validate(&owner)?;
+
+        // an owner holds at most one nft of each unique kind
+        if let Some(kind) = &kind {
+            if self.unique_kinds.has(deps.storage, kind) && self.has_badge(deps.storage, owner_addr.as_str(), kind, env.block.time)? {
+                return Err(ContractError::AlreadyHoldsBadge { kind: kind.clone() });
+            }
+        }
+
         let nft_info = NftInfo {
             id: nft_id.clone(),
             owner: owner_addr.to_string(),
             nft_uri,
             equiped: true,
             is_admitted: false,
+            metadata_hash,
+            class_id,
+            expires_at: class
+                .as_ref()
+                .and_then(|class| class.expires_after)
+                .map(|seconds| env.block.time.plus_seconds(seconds)),
+            kind,
+            issuer: issuer.to_string(),
+            issued_at_height: env.block.height,
+            revoke_reason: None,
+            base_uri_version,
         };
 
         // add the newly created token to tokens in storage
-        self.nfts.save(deps.storage, &nft_id, &nft_info)?;
+        self.save_nft(deps.storage, None, &nft_info, env.block.height)?;
+
+        // count the nft against the supply of its badge class
+        if let Some(class) = &class {
+            self.badge_classes.save(deps.storage, &class.id, class)?;
+        }
 
         // increase the number of nfts in storage using incresment function
         self.increment_nfts(deps.storage)?;
-        
-        Ok(Response::new()
-            .add_attribute("action", "mint")
-            .add_attribute("minter", info.sender)
-            .add_attribute("nft_id", nft_id)
-            .add_attribute("owner", owner))
+
+        Ok(nft_info)
+    }
+
+    // check_not_offered returns an error if a pending offer reserves the nft id.
+    // An expired offer no longer reserves it and is removed
+    pub fn check_not_offered(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        nft_id: &str,
+    ) -> Result<(), ContractError> {
+        if let Some(offer) = self.offers.may_load(storage, nft_id)? {
+            if !offer.expires.is_expired(&env.block) {
+                return Err(ContractError::NftAlreadyExists { nft_id: nft_id.to_string() });
+            }
+            self.offers.remove(storage, nft_id)?;
+        }
+        Ok(())
+    }
+
+    // check_not_reserved returns an error if the nft id is in the namespace of a campaign.
+    // Nfts claimed from a campaign get the ids `campaign_id/N`, which cannot be minted otherwise
+    pub fn check_not_reserved(&self, storage: &dyn Storage, nft_id: &str) -> Result<(), ContractError> {
+        for (pos, _) in nft_id.match_indices('/') {
+            let campaign_id = &nft_id[..pos];
+            if self.campaigns.has(storage, campaign_id) {
+                return Err(ContractError::NftIdReserved {
+                    nft_id: nft_id.to_string(),
+                    campaign_id: campaign_id.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // collect_fee checks the funds sent with a message cover the mint fee
+    // and returns the message refunding everything paid in excess
+    pub fn collect_fee(
+        &self,
+        storage: &dyn Storage,
+        info: &MessageInfo,
+    ) -> Result<Option<BankMsg>, ContractError> {
+        let mut refund = info.funds.clone();
+        if let Some(fee) = self.mint_fee.may_load(storage)? {
+            let paid = refund
+                .iter_mut()
+                .find(|coin| coin.denom == fee.denom && coin.amount >= fee.amount)
+                .ok_or(ContractError::InsufficientFee { required: fee.clone() })?;
+            paid.amount -= fee.amount;
+        }
+        refund.retain(|coin| !coin.amount.is_zero());
+
+        if refund.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: refund,
+        }))
+    }
+
+    // prepare_hooks returns the messages notifying every registered hook of a change of the nft
+    pub fn prepare_hooks(
+        &self,
+        storage: &dyn Storage,
+        event: AbtEvent,
+        nft_info: &NftInfo,
+    ) -> StdResult<Vec<SubMsg>> {
+        self.prepare_hook_msgs(storage, AbtHookMsg::new(event, &nft_info.id, &nft_info.owner))
+    }
+
+    // prepare_hook_msgs returns the messages sending the given message to every registered hook
+    pub fn prepare_hook_msgs(
+        &self,
+        storage: &dyn Storage,
+        msg: AbtHookMsg,
+    ) -> StdResult<Vec<SubMsg>> {
+        self.hooks.prepare_hooks(storage, |hook| {
+            msg.clone().into_cosmos_msg(hook).map(SubMsg::new)
+        })
+    }
+
+    // transition appends a change of a nft to its history and returns the event announcing it
+    pub fn transition(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        action: &str,
+        nft_info: &NftInfo,
+        sender: &Addr,
+        state_before: Option<NftStatus>,
+    ) -> StdResult<Event> {
+        self.append_history(storage, env, action, &nft_info.id, sender)?;
+        Ok(nft_event(action, nft_info, sender, state_before, env))
+    }
+
+    // append_history appends a change of a nft to its history
+    pub fn append_history(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        action: &str,
+        nft_id: &str,
+        sender: &Addr,
+    ) -> StdResult<()> {
+        let seq = self.history
+            .prefix(nft_id)
+            .keys(storage, None, None, Order::Descending)
+            .next()
+            .transpose()?
+            .map_or(0, |last| last + 1);
+        let entry = HistoryEntry {
+            seq,
+            action: action.to_string(),
+            actor: sender.clone(),
+            height: env.block.height,
+            time: env.block.time,
+        };
+        self.history.save(storage, (nft_id, seq), &entry)
+    }
+
+    // check_issuer returns an error if the issuer may not mint nfts of the given badge class,
+    // or nfts without class if no class is given
+    pub fn check_issuer(
+        &self,
+        storage: &dyn Storage,
+        issuer: &Addr,
+        class_id: &Option<String>,
+    ) -> Result<(), ContractError> {
+        let minter = self.minter.load(storage)?;
+        if *issuer == minter {
+            return Ok(());
+        }
+        match class_id {
+            Some(class_id) if self.badge_classes.load(storage, class_id)?.issuers.contains(issuer) => Ok(()),
+            _ => Err(ContractError::NotMinter {}),
+        }
+    }
+
+    // check_receive_policy returns an error if the owner does not accept nfts from the issuer.
+    // Owners that require claims accept nfts only if they explicitly consent to them
+    pub fn check_receive_policy(
+        &self,
+        storage: &dyn Storage,
+        owner: &Addr,
+        issuer: &Addr,
+        consented: bool,
+    ) -> Result<(), ContractError> {
+        let policy = self.receive_policies.may_load(storage, owner)?.unwrap_or(ReceivePolicy::AcceptAll);
+        let accepted = match &policy {
+            ReceivePolicy::AcceptAll => true,
+            ReceivePolicy::AllowList(issuers) => issuers.iter().any(|allowed| allowed == issuer.as_str()),
+            ReceivePolicy::BlockList(issuers) => !issuers.iter().any(|blocked| blocked == issuer.as_str()),
+            ReceivePolicy::RequireClaim => {
+                if !consented {
+                    return Err(ContractError::ClaimRequired {});
+                }
+                true
+            }
+        };
+        if !accepted {
+            return Err(ContractError::RecipientRefused { issuer: issuer.to_string() });
+        }
+        Ok(())
     }
 
+
     // execute_unequip is a function that allows the owner of a nft to unequip it by set the equiped field to false
     pub fn execute_unequip(
         &self,
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         nft_id: String,
     ) -> Result<Response, ContractError> {
@@ -168,7 +821,7 @@ impl<'a> Aura4973<'a>{
         let mut nft_info = self.nfts.load(deps.storage, &nft_id)?;
 
         // check if the owner of the nft is the sender
-        if nft_info.owner != info.sender.to_string() {
+        if nft_info.owner != info.sender {
             return Err(ContractError::Unauthorized {});
         }
 
@@ -178,13 +831,19 @@ impl<'a> Aura4973<'a>{
         }
 
         // set the equiped field to false
+        let old = nft_info.clone();
         nft_info.equiped = false;
 
         // save the nft info
-        self.nfts.save(deps.storage, &nft_id, &nft_info)?;
+        self.save_nft(deps.storage, Some(&old), &nft_info, env.block.height)?;
+
+        // notify the hooks
+        let hooks = self.prepare_hooks(deps.storage, AbtEvent::Unequip, &nft_info)?;
 
         // return response
         Ok(Response::new()
+            .add_submessages(hooks)
+            .add_event(self.transition(deps.storage, &env, "unequip", &nft_info, &info.sender, Some(old.status()))?)
             .add_attribute("action", "unequip")
             .add_attribute("nft_id", nft_id)
             .add_attribute("owner", info.sender))
@@ -194,7 +853,7 @@ impl<'a> Aura4973<'a>{
     pub fn execute_equip(
         &self,
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         nft_id: String,
     ) -> Result<Response, ContractError> {
@@ -202,7 +861,7 @@ impl<'a> Aura4973<'a>{
         let mut nft_info = self.nfts.load(deps.storage, &nft_id)?;
 
         // check if the owner of the nft is the sender
-        if nft_info.owner != info.sender.to_string() {
+        if nft_info.owner != info.sender {
             return Err(ContractError::Unauthorized {});
         }
 
@@ -212,13 +871,19 @@ impl<'a> Aura4973<'a>{
         }
 
         // set the equiped field to true
+        let old = nft_info.clone();
         nft_info.equiped = true;
 
         // save the nft info
-        self.nfts.save(deps.storage, &nft_id, &nft_info)?;
+        self.save_nft(deps.storage, Some(&old), &nft_info, env.block.height)?;
+
+        // notify the hooks
+        let hooks = self.prepare_hooks(deps.storage, AbtEvent::Equip, &nft_info)?;
 
         // return response
         Ok(Response::new()
+            .add_submessages(hooks)
+            .add_event(self.transition(deps.storage, &env, "equip", &nft_info, &info.sender, Some(old.status()))?)
             .add_attribute("action", "equip")
             .add_attribute("nft_id", nft_id)
             .add_attribute("owner", info.sender))
@@ -228,7 +893,7 @@ impl<'a> Aura4973<'a>{
     pub fn execute_unadmit(
         &self,
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         nft_id: String,
     ) -> Result<Response, ContractError> {
@@ -239,105 +904,2035 @@ impl<'a> Aura4973<'a>{
         }
 
         // get information of nft by id
-        let nft_info = self.nfts.load(deps.storage, &nft_id)?;
+        let mut nft_info = self.nfts.load(deps.storage, &nft_id)?;
 
         // check if the nft is already unadmitted
         if nft_info.is_admitted {
             return Err(ContractError::NftAlreadyUnadmitted {});
         }
-        else {
-            // set the admitted field to true
-            let mut nft_info = self.nfts.load(deps.storage, &nft_id)?;
-            nft_info.is_admitted = true;
 
-            // save the nft info
-            self.nfts.save(deps.storage, &nft_id, &nft_info)?
-        }
+        // set the admitted field to true
+        let old = nft_info.clone();
+        nft_info.is_admitted = true;
+
+        // save the nft info
+        self.save_nft(deps.storage, Some(&old), &nft_info, env.block.height)?;
+
+        // notify the hooks
+        let hooks = self.prepare_hooks(deps.storage, AbtEvent::Revoke, &nft_info)?;
 
         // return response
         Ok(Response::new()
+            .add_submessages(hooks)
+            .add_event(self.transition(deps.storage, &env, "revoke", &nft_info, &info.sender, Some(old.status()))?)
             .add_attribute("action", "unadmit")
+            .add_attribute("owner", &nft_info.owner)
             .add_attribute("nft_id", nft_id)
             .add_attribute("minter", info.sender))
     }
 
-    // contract_info returns the contract info
-    fn contract_info(&self, deps: Deps) -> StdResult<ContractInfoResponse> {
-        self.contract_info.load(deps.storage)
-    }
-
-    // num_nfts returns the number of distributed nfts in the contract
-    fn num_nfts(&self, deps: Deps) -> StdResult<NumNftsResponse> {
-        let count = self.nft_count.load(deps.storage)?;
-        Ok(NumNftsResponse { count })
-    }
-
-    // owner_of returns the owner of the token with the given id
-    pub fn owner_of(
+    // execute_destroy is a function that allows the issuer of a nft to destroy it, keeping a tombstone of it
+    pub fn execute_destroy(
         &self,
-        deps: Deps,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
         nft_id: String,
-    ) -> StdResult<OwnerOfResponse> {
-        let info = self.nfts.load(deps.storage, &nft_id)?;
-        Ok(OwnerOfResponse {
-            owner: info.owner,
-        })
-    }
+    ) -> Result<Response, ContractError> {
+        // get information of nft by id
+        let nft_info = self.nfts.load(deps.storage, &nft_id)?;
 
-    // nft_info returns the information of the nft with the given id
-    pub fn nft_info(
-        &self,
-        deps: Deps,
-        nft_id: String,
-    ) -> StdResult<NftInfo> {
-        let info = self.nfts.load(deps.storage, &nft_id)?;
-        Ok(NftInfo {
-            id: info.id,
-            owner: info.owner,
-            nft_uri: info.nft_uri,
-            equiped: info.equiped,
-            is_admitted: info.is_admitted,
-        })
-    }
+        // check if the sender is the admin, or the issuer of the nft and still allowed to mint it
+        let admin = self.admin.load(deps.storage)?;
+        let is_issuer = nft_info.issuer == info.sender
+            && self.check_issuer(deps.storage, &info.sender, &nft_info.class_id).is_ok();
+        if info.sender != admin && !is_issuer {
+            return Err(ContractError::Unauthorized {});
+        }
 
-    // all_unequipped_nft_of is a function that returns all the nfts of a given owner that are unequipped
-    pub fn all_unequipped_nft_of(
-        &self,
-        deps: Deps,
-        owner: String,
-    ) -> StdResult<Vec<NftInfo>> {
-        let mut unequipped_nfts: Vec<NftInfo> = vec![];
-        // load all nfts from storage prefixed by owner index 
-        self.nfts.idx.owner
-            .prefix(owner)
-            .range(deps.storage, None, None, Order::Ascending)
-            .for_each(|item| {
-                let (_, nft_info) = item.unwrap();
-                if !nft_info.equiped && !nft_info.is_admitted {
-                    unequipped_nfts.push(nft_info);
-                }
-            });
-        Ok(unequipped_nfts)
+        // remove the nft and uncount it
+        self.update_counts(deps.storage, &nft_info, false, env.block.height)?;
+        self.nfts.replace(deps.storage, &nft_id, None, Some(&nft_info), env.block.height)?;
+        let num_burned = self.num_burned.may_load(deps.storage)?.unwrap_or_default();
+        self.num_burned.save(deps.storage, &increment_count(num_burned)?)?;
+
+        // keep a tombstone so that the id is never reused
+        let tombstone = Tombstone {
+            owner: nft_info.owner.clone(),
+            issuer: nft_info.issuer.clone(),
+            burned_at: env.block.time,
+            burned_by: info.sender.clone(),
+        };
+        self.tombstones.save(deps.storage, &nft_id, &tombstone)?;
+
+        // notify the hooks
+        let hooks = self.prepare_hooks(deps.storage, AbtEvent::Burn, &nft_info)?;
+        self.append_history(deps.storage, &env, "burn", &nft_id, &info.sender)?;
+
+        // return response
+        Ok(Response::new()
+            .add_submessages(hooks)
+            .add_event(burn_event(&nft_info, &info.sender, &env))
+            .add_attribute("action", "destroy")
+            .add_attribute("nft_id", nft_id)
+            .add_attribute("owner", nft_info.owner)
+            .add_attribute("issuer", info.sender))
     }
 
-    // all_equipped_nft_of is a function that returns all the nfts of a given owner that are equipped
-    pub fn all_equipped_nft_of(
+    // execute_revoke_issued_by is a function that allows the admin to revoke the nfts issued by an address
+    // from a given height on. It walks a bounded page of the nfts of the issuer and returns where to resume
+    pub fn execute_revoke_issued_by(
         &self,
-        deps: Deps,
-        owner: String,
-    ) -> StdResult<Vec<NftInfo>> {
-        let mut equipped_nfts: Vec<NftInfo> = vec![];
-        // load all nfts from storage prefixed by owner index 
-        self.nfts.idx.owner
-            .prefix(owner)
-            .range(deps.storage, None, None, Order::Ascending)
-            .for_each(|item| {
-                let (_, nft_info) = item.unwrap();
-                if nft_info.equiped && !nft_info.is_admitted {
-                    equipped_nfts.push(nft_info);
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: RevokeIssuedByMsg,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let limit = msg.limit.unwrap_or(DEFAULT_REVOKE_LIMIT).min(MAX_REVOKE_LIMIT) as usize;
+        let start = msg.start_after.map(Bound::exclusive);
+        let nfts = self.nfts.idx.issuer
+            .prefix(msg.issuer.clone())
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, nft_info)| nft_info))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        // a full page may be followed by another one
+        let next = match nfts.last() {
+            Some(last) if nfts.len() == limit => Some(last.id.clone()),
+            _ => None,
+        };
+
+        let mut response = Response::new();
+        let mut revoked = 0u64;
+        for mut nft_info in nfts {
+            if nft_info.is_admitted || nft_info.issued_at_height < msg.since_height {
+                continue;
+            }
+
+            let old = nft_info.clone();
+            nft_info.is_admitted = true;
+            nft_info.revoke_reason = Some(msg.reason.clone());
+            self.save_nft(deps.storage, Some(&old), &nft_info, env.block.height)?;
+            revoked += 1;
+
+            let hooks = self.prepare_hooks(deps.storage, AbtEvent::Revoke, &nft_info)?;
+            response = response
+                .add_submessages(hooks)
+                .add_event(
+                    self.transition(deps.storage, &env, "revoke", &nft_info, &info.sender, Some(old.status()))?
+                        .add_attribute("reason", &msg.reason),
+                );
+        }
+
+        let data = RevokeIssuedByResponse { revoked, next: next.clone() };
+        Ok(response
+            .set_data(to_json_binary(&data)?)
+            .add_attribute("action", "revoke_issued_by")
+            .add_attribute("admin", info.sender)
+            .add_attribute("issuer", msg.issuer)
+            .add_attribute("revoked", revoked.to_string())
+            .add_attributes(next.map(|next| ("next", next))))
+    }
+
+    // execute_offer is a function that allows an issuer to offer a nft, which is minted once the recipient accepts it
+    pub fn execute_offer(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: OfferMsg,
+    ) -> Result<Response, ContractError> {
+        self.check_issuer(deps.storage, &info.sender, &msg.class_id)?;
+
+        // the recipient must accept offers from the sender
+        let owner_addr = deps.api.addr_validate(&msg.owner)?;
+        self.check_receive_policy(deps.storage, &owner_addr, &info.sender, true)?;
+
+        // the id must be free, an expired offer of it is replaced
+        if self.nfts.key(&msg.nft_id).has(deps.storage) {
+            return Err(ContractError::NftAlreadyExists { nft_id: msg.nft_id });
+        }
+        if self.tombstones.has(deps.storage, &msg.nft_id) {
+            return Err(ContractError::NftDestroyed { nft_id: msg.nft_id });
+        }
+        self.check_not_offered(deps.storage, &env, &msg.nft_id)?;
+        self.check_not_reserved(deps.storage, &msg.nft_id)?;
+
+        // check the metadata now, accepting the offer mints the nft with it as is
+        let metadata = self.check_metadata(deps.storage, msg.class_id, msg.nft_uri, msg.metadata_hash, msg.kind)?;
+
+        let offer = Offer {
+            nft_id: msg.nft_id.clone(),
+            owner: owner_addr.to_string(),
+            nft_uri: metadata.nft_uri,
+            base_uri_version: metadata.base_uri_version,
+            metadata_hash: metadata.metadata_hash,
+            class_id: metadata.class_id,
+            kind: metadata.kind,
+            issuer: info.sender.clone(),
+            expires: msg.expires.unwrap_or(Expiration::Never {}),
+        };
+        self.offers.save(deps.storage, &msg.nft_id, &offer)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "offer")
+            .add_attribute("issuer", info.sender)
+            .add_attribute("nft_id", msg.nft_id)
+            .add_attribute("owner", offer.owner))
+    }
+
+    // execute_accept is a function that allows the recipient of an offer to accept it and mint its nft
+    pub fn execute_accept(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        nft_id: String,
+    ) -> Result<Response, ContractError> {
+        let offer = self.offers.load(deps.storage, &nft_id)?;
+
+        // check if the recipient is the sender
+        if offer.owner != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        if offer.expires.is_expired(&env.block) {
+            return Err(ContractError::OfferExpired {});
+        }
+
+        // the recipient pays the mint fee, as claimers do
+        let refund = self.collect_fee(deps.storage, &info)?;
+
+        self.offers.remove(deps.storage, &nft_id)?;
+
+        // the metadata was checked when offering
+        let metadata = NftMetadata {
+            class_id: offer.class_id,
+            nft_uri: offer.nft_uri,
+            base_uri_version: offer.base_uri_version,
+            metadata_hash: offer.metadata_hash,
+            kind: offer.kind,
+        };
+        let nft_info = self.mint_checked_nft(deps.branch(), &env, &offer.issuer, offer.nft_id, offer.owner, metadata)?;
+        let hooks = self.prepare_hooks(deps.storage, AbtEvent::Mint, &nft_info)?;
+
+        // return response
+        Ok(Response::new()
+            .add_messages(refund)
+            .add_submessages(hooks)
+            .add_event(self.transition(deps.storage, &env, "mint", &nft_info, &info.sender, None)?)
+            .add_attribute("action", "accept")
+            .add_attribute("minter", offer.issuer)
+            .add_attribute("nft_id", nft_info.id)
+            .add_attribute("owner", nft_info.owner))
+    }
+
+    // execute_reject is a function that allows the recipient of an offer to reject it
+    pub fn execute_reject(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        nft_id: String,
+    ) -> Result<Response, ContractError> {
+        let offer = self.offers.load(deps.storage, &nft_id)?;
+
+        // check if the recipient is the sender
+        if offer.owner != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        self.offers.remove(deps.storage, &nft_id)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "reject")
+            .add_attribute("nft_id", nft_id)
+            .add_attribute("owner", info.sender))
+    }
+
+    // execute_cancel_offer is a function that allows the issuer of an offer to withdraw it
+    pub fn execute_cancel_offer(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        nft_id: String,
+    ) -> Result<Response, ContractError> {
+        let offer = self.offers.load(deps.storage, &nft_id)?;
+
+        // check if the issuer of the offer is the sender
+        if offer.issuer != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        self.offers.remove(deps.storage, &nft_id)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "cancel_offer")
+            .add_attribute("nft_id", nft_id)
+            .add_attribute("issuer", info.sender))
+    }
+
+    // execute_create_campaign is a function that allows an issuer to create a campaign of nfts that users claim
+    pub fn execute_create_campaign(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: CampaignMsg,
+    ) -> Result<Response, ContractError> {
+        self.check_issuer(deps.storage, &info.sender, &msg.class_id)?;
+
+        if self.campaigns.has(deps.storage, &msg.id) {
+            return Err(ContractError::CampaignExists { campaign_id: msg.id });
+        }
+
+        // the ids of claimed nfts must not be taken by nfts or offers, not even destroyed ones
+        let first = format!("{}/", msg.id);
+        let after_last = format!("{}0", msg.id);
+        let min = || Some(Bound::inclusive(first.as_str()));
+        let max = || Some(Bound::exclusive(after_last.as_str()));
+        if self.nfts.keys(deps.storage, min(), max(), Order::Ascending).next().is_some()
+            || self.offers.keys(deps.storage, min(), max(), Order::Ascending).next().is_some()
+            || self.tombstones.keys(deps.storage, min(), max(), Order::Ascending).next().is_some()
+        {
+            return Err(ContractError::CampaignIdsInUse { campaign_id: msg.id });
+        }
+
+        // users must prove they may claim
+        if msg.merkle_root.is_none() && msg.code_keys.is_empty() {
+            return Err(ContractError::MissingClaimCondition {});
+        }
+        if let Some(merkle_root) = &msg.merkle_root {
+            validate_sha256(merkle_root)?;
+        }
+        if let (Some(start), Some(end)) = (msg.start, msg.end) {
+            if start >= end {
+                return Err(ContractError::InvalidCampaignPeriod {});
+            }
+        }
+
+        // check the metadata now, nfts claimed with a secret code are minted with it as is.
+        // Merkle claims of campaigns without class may bring the uri, it is checked when claiming
+        let (nft_uri, base_uri_version, metadata_hash, kind) = match (&msg.class_id, msg.nft_uri) {
+            (None, None) if msg.code_keys.is_empty() => {
+                validate_metadata_hash(&msg.metadata_hash)?;
+                (None, None, msg.metadata_hash, msg.kind)
+            }
+            (_, nft_uri) => {
+                let metadata = self.check_metadata(deps.storage, msg.class_id.clone(), nft_uri, msg.metadata_hash, msg.kind)?;
+                (Some(metadata.nft_uri), metadata.base_uri_version, metadata.metadata_hash, metadata.kind)
+            }
+        };
+
+        for code_key in &msg.code_keys {
+            validate_public_key(code_key)?;
+            self.campaign_codes.save(deps.storage, (&msg.id, code_key.as_slice()), &Empty {})?;
+        }
+
+        let campaign = Campaign {
+            id: msg.id.clone(),
+            issuer: info.sender.clone(),
+            nft_uri,
+            base_uri_version,
+            metadata_hash,
+            class_id: msg.class_id,
+            kind,
+            merkle_root: msg.merkle_root,
+            max_claims: msg.max_claims,
+            claimed: 0,
+            start: msg.start,
+            end: msg.end,
+        };
+        self.campaigns.save(deps.storage, &msg.id, &campaign)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "create_campaign")
+            .add_attribute("campaign_id", msg.id)
+            .add_attribute("issuer", info.sender))
+    }
+
+    // execute_claim is a function that allows a user to claim a nft of a campaign with a merkle proof or a secret code
+    pub fn execute_claim(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        campaign_id: String,
+        proof: ClaimProof,
+    ) -> Result<Response, ContractError> {
+        let mut campaign = self.campaigns.load(deps.storage, &campaign_id)?;
+
+        // the claimer pays the mint fee
+        let refund = self.collect_fee(deps.storage, &info)?;
+
+        // check the campaign is running and not exhausted
+        if campaign.start.is_some_and(|start| env.block.time < start) {
+            return Err(ContractError::CampaignNotStarted {});
+        }
+        if campaign.end.is_some_and(|end| env.block.time >= end) {
+            return Err(ContractError::CampaignEnded {});
+        }
+        if campaign.max_claims.is_some_and(|max_claims| campaign.claimed >= max_claims) {
+            return Err(ContractError::CampaignCapReached {});
+        }
+
+        // verify the proof and get the key of the claim and the metadata of the nft
+        let (claim_key, metadata) = match proof {
+            ClaimProof::Merkle { claim_id, nft_uri, proof } => {
+                let root = campaign.merkle_root.as_ref().ok_or(ContractError::InvalidProof {})?;
+                let leaf = hash_fields(&[&claim_id, info.sender.as_str(), &nft_uri]);
+                if !verify_merkle_proof(leaf, &proof, root)? {
+                    return Err(ContractError::InvalidProof {});
+                }
+                let metadata = self.check_metadata(
+                    deps.storage,
+                    campaign.class_id.clone(),
+                    Some(nft_uri),
+                    campaign.metadata_hash.clone(),
+                    campaign.kind.clone(),
+                )?;
+                (claim_id, metadata)
+            }
+            ClaimProof::Secret { public_key, signature } => {
+                if !self.campaign_codes.has(deps.storage, (&campaign_id, public_key.as_slice())) {
+                    return Err(ContractError::InvalidProof {});
+                }
+                // the code signs the claimer, so a broadcast claim cannot be taken over
+                let message = hash_fields(&[env.contract.address.as_str(), &campaign_id, info.sender.as_str()]);
+                if !matches!(deps.api.secp256k1_verify(&message, &signature, &public_key), Ok(true)) {
+                    return Err(ContractError::InvalidProof {});
+                }
+                // the metadata was checked when creating the campaign
+                let metadata = NftMetadata {
+                    class_id: campaign.class_id.clone(),
+                    nft_uri: campaign.nft_uri.clone().ok_or(ContractError::MissingNftUri {})?,
+                    base_uri_version: campaign.base_uri_version,
+                    metadata_hash: campaign.metadata_hash.clone(),
+                    kind: campaign.kind.clone(),
+                };
+                (public_key.to_hex(), metadata)
+            }
+        };
+
+        // every claim can be used once
+        if self.claims.has(deps.storage, (&campaign_id, &claim_key)) {
+            return Err(ContractError::AlreadyClaimed {});
+        }
+        self.claims.save(deps.storage, (&campaign_id, &claim_key), &Empty {})?;
+
+        campaign.claimed += 1;
+        self.campaigns.save(deps.storage, &campaign_id, &campaign)?;
+
+        // the sender asked for the nft, so its receive policy does not apply
+        let nft_id = format!("{}/{}", campaign_id, campaign.claimed);
+        let nft_info = self.mint_checked_nft(deps.branch(), &env, &campaign.issuer, nft_id, info.sender.to_string(), metadata)?;
+        let hooks = self.prepare_hooks(deps.storage, AbtEvent::Mint, &nft_info)?;
+
+        // return response
+        Ok(Response::new()
+            .add_messages(refund)
+            .add_submessages(hooks)
+            .add_event(self.transition(deps.storage, &env, "mint", &nft_info, &info.sender, None)?)
+            .add_attribute("action", "claim")
+            .add_attribute("campaign_id", campaign_id)
+            .add_attribute("minter", campaign.issuer)
+            .add_attribute("nft_id", nft_info.id)
+            .add_attribute("owner", nft_info.owner))
+    }
+
+    // execute_recover is a function that allows the minter to move all nfts of a lost address to a new one.
+    // The recovery is executed right away only if no delay and no confirmation are configured
+    pub fn execute_recover(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        from: String,
+        to: String,
+    ) -> Result<Response, ContractError> {
+        // check if the minter is the sender
+        let minter = self.minter.load(deps.storage)?;
+        if info.sender != minter {
+            return Err(ContractError::NotMinter {});
+        }
+
+        let from = deps.api.addr_validate(&from)?;
+        let to = deps.api.addr_validate(&to)?;
+        if from == to {
+            return Err(ContractError::RecoveryToSelf {});
+        }
+
+        // only one recovery of an address can be pending
+        if self.pending_recoveries.has(deps.storage, &from) {
+            return Err(ContractError::RecoveryPending { from: from.to_string() });
+        }
+
+        let config = self.recovery_config.load(deps.storage)?;
+        if config.delay == 0 && !config.require_confirmation {
+            let rebind = self.rebind_nfts(deps.storage, &env, &info.sender, &from, &to, None)?;
+
+            // the remaining pages are moved by FinalizeRecovery
+            if let Some(next) = &rebind.next {
+                let recovery = PendingRecovery {
+                    from: from.clone(),
+                    to: to.clone(),
+                    executable_at: env.block.time,
+                    confirmed: true,
+                    start_after: Some(next.clone()),
+                };
+                self.pending_recoveries.save(deps.storage, &from, &recovery)?;
+            }
+            return Ok(rebind_response("recover", &from, &to, rebind));
+        }
+
+        let recovery = PendingRecovery {
+            from: from.clone(),
+            to: to.clone(),
+            executable_at: env.block.time.plus_seconds(config.delay),
+            confirmed: !config.require_confirmation,
+            start_after: None,
+        };
+        self.pending_recoveries.save(deps.storage, &from, &recovery)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "request_recovery")
+            .add_attribute("from", from)
+            .add_attribute("to", to)
+            .add_attribute("executable_at", recovery.executable_at.to_string()))
+    }
+
+    // execute_confirm_recovery is a function that allows the new owner to confirm a pending recovery
+    pub fn execute_confirm_recovery(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        from: String,
+    ) -> Result<Response, ContractError> {
+        let from = deps.api.addr_validate(&from)?;
+        let mut recovery = self.pending_recoveries.load(deps.storage, &from)?;
+
+        // check if the new owner is the sender
+        if info.sender != recovery.to {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        recovery.confirmed = true;
+        self.pending_recoveries.save(deps.storage, &from, &recovery)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "confirm_recovery")
+            .add_attribute("from", from)
+            .add_attribute("to", info.sender))
+    }
+
+    // execute_finalize_recovery is a function that executes a page of a confirmed recovery once its delay has passed.
+    // The recovery stays pending until its last page is executed
+    pub fn execute_finalize_recovery(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        from: String,
+    ) -> Result<Response, ContractError> {
+        let from = deps.api.addr_validate(&from)?;
+        let mut recovery = self.pending_recoveries.load(deps.storage, &from)?;
+
+        if env.block.time < recovery.executable_at {
+            return Err(ContractError::RecoveryLocked { executable_at: recovery.executable_at });
+        }
+        if !recovery.confirmed {
+            return Err(ContractError::RecoveryNotConfirmed {});
+        }
+
+        let rebind = self.rebind_nfts(deps.storage, &env, &info.sender, &recovery.from, &recovery.to, recovery.start_after.clone())?;
+        match &rebind.next {
+            Some(next) => {
+                recovery.start_after = Some(next.clone());
+                self.pending_recoveries.save(deps.storage, &from, &recovery)?;
+            }
+            None => self.pending_recoveries.remove(deps.storage, &from),
+        }
+
+        // return response
+        Ok(rebind_response("recover", &recovery.from, &recovery.to, rebind))
+    }
+
+    // execute_cancel_recovery is a function that allows the old owner or the minter to cancel a pending recovery
+    pub fn execute_cancel_recovery(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        from: String,
+    ) -> Result<Response, ContractError> {
+        let from = deps.api.addr_validate(&from)?;
+        let recovery = self.pending_recoveries.load(deps.storage, &from)?;
+
+        // check if the old owner or the minter is the sender
+        let minter = self.minter.load(deps.storage)?;
+        if info.sender != recovery.from && info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        self.pending_recoveries.remove(deps.storage, &from);
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "cancel_recovery")
+            .add_attribute("from", from)
+            .add_attribute("sender", info.sender))
+    }
+
+    // execute_update_recovery_config is a function that allows the admin to replace the rules for recoveries
+    pub fn execute_update_recovery_config(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        config: RecoveryConfig,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        validate_recovery_config(&config)?;
+        self.recovery_config.save(deps.storage, &config)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "update_recovery_config")
+            .add_attribute("admin", info.sender))
+    }
+
+    // execute_set_guardians is a function that allows an owner to designate the guardians of its nfts
+    pub fn execute_set_guardians(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        guardians: Vec<String>,
+        threshold: u32,
+    ) -> Result<Response, ContractError> {
+        // a new guardian set invalidates the approvals of the old one
+        self.clear_social_recovery(deps.storage, &info.sender)?;
+
+        if guardians.is_empty() {
+            self.guardians.remove(deps.storage, &info.sender);
+            return Ok(Response::new()
+                .add_attribute("action", "remove_guardians")
+                .add_attribute("owner", info.sender));
+        }
+
+        let mut guardians = guardians
+            .iter()
+            .map(|guardian| deps.api.addr_validate(guardian))
+            .collect::<StdResult<Vec<_>>>()?;
+        guardians.sort();
+        guardians.dedup();
+
+        if threshold == 0 || threshold as usize > guardians.len() {
+            return Err(ContractError::InvalidThreshold {});
+        }
+
+        let guardian_set = GuardianSet { guardians, threshold };
+        self.guardians.save(deps.storage, &info.sender, &guardian_set)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "set_guardians")
+            .add_attribute("owner", info.sender)
+            .add_attribute("threshold", threshold.to_string()))
+    }
+
+    // execute_approve_social_recovery is a function that allows a guardian to approve moving the nfts of an owner
+    pub fn execute_approve_social_recovery(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        owner: String,
+        new_owner: String,
+    ) -> Result<Response, ContractError> {
+        let owner = deps.api.addr_validate(&owner)?;
+        let new_owner = deps.api.addr_validate(&new_owner)?;
+        if owner == new_owner {
+            return Err(ContractError::RecoveryToSelf {});
+        }
+
+        // check if a guardian of the owner is the sender
+        let guardian_set = self
+            .guardians
+            .may_load(deps.storage, &owner)?
+            .ok_or(ContractError::NotGuardian {})?;
+        if !guardian_set.guardians.contains(&info.sender) {
+            return Err(ContractError::NotGuardian {});
+        }
+
+        // a guardian approves one new owner at a time, approving another one moves its approval
+        match self.guardian_approval(deps.storage, &owner, &info.sender)? {
+            Some(approved) if approved == new_owner => return Err(ContractError::AlreadyApproved {}),
+            Some(approved) => self.remove_approval(deps.storage, &owner, &approved, &info.sender)?,
+            None => {}
+        }
+        let mut approvals = self
+            .social_recovery_approvals
+            .may_load(deps.storage, (&owner, &new_owner))?
+            .unwrap_or_default();
+        approvals.push(info.sender.clone());
+        self.social_recovery_approvals.save(deps.storage, (&owner, &new_owner), &approvals)?;
+
+        self.refresh_social_recovery(deps.storage, &env, &owner, guardian_set.threshold)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "approve_social_recovery")
+            .add_attribute("owner", owner)
+            .add_attribute("new_owner", new_owner)
+            .add_attribute("guardian", info.sender)
+            .add_attribute("approvals", approvals.len().to_string()))
+    }
+
+    // execute_withdraw_social_recovery_approval is a function that allows a guardian to withdraw its approval
+    // of a recovery
+    pub fn execute_withdraw_social_recovery_approval(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        owner: String,
+    ) -> Result<Response, ContractError> {
+        let owner = deps.api.addr_validate(&owner)?;
+        let approved = self
+            .guardian_approval(deps.storage, &owner, &info.sender)?
+            .ok_or(ContractError::NoApproval {})?;
+        self.remove_approval(deps.storage, &owner, &approved, &info.sender)?;
+
+        // approvals are cleared when the guardians change, so the owner still has guardians
+        let guardian_set = self.guardians.load(deps.storage, &owner)?;
+        self.refresh_social_recovery(deps.storage, &env, &owner, guardian_set.threshold)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "withdraw_social_recovery_approval")
+            .add_attribute("owner", owner)
+            .add_attribute("new_owner", approved)
+            .add_attribute("guardian", info.sender))
+    }
+
+    // guardian_approval returns the new owner of an owner approved by the guardian if any
+    fn guardian_approval(
+        &self,
+        storage: &dyn Storage,
+        owner: &Addr,
+        guardian: &Addr,
+    ) -> StdResult<Option<Addr>> {
+        // every approved new owner has at least one approval, so there are at most as many as guardians
+        for item in self.social_recovery_approvals.prefix(owner).range(storage, None, None, Order::Ascending) {
+            let (new_owner, approvals) = item?;
+            if approvals.contains(guardian) {
+                return Ok(Some(new_owner));
+            }
+        }
+        Ok(None)
+    }
+
+    // remove_approval removes the approval of a new owner of an owner by the guardian
+    fn remove_approval(
+        &self,
+        storage: &mut dyn Storage,
+        owner: &Addr,
+        new_owner: &Addr,
+        guardian: &Addr,
+    ) -> StdResult<()> {
+        let mut approvals = self.social_recovery_approvals.load(storage, (owner, new_owner))?;
+        approvals.retain(|approval| approval != guardian);
+        if approvals.is_empty() {
+            self.social_recovery_approvals.remove(storage, (owner, new_owner));
+        } else {
+            self.social_recovery_approvals.save(storage, (owner, new_owner), &approvals)?;
+        }
+        Ok(())
+    }
+
+    // refresh_social_recovery starts the recovery of an owner to the first new owner approved by enough guardians,
+    // and stops it once its new owner lost the approvals it needs. A recovery that has started moving nfts is kept
+    fn refresh_social_recovery(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        owner: &Addr,
+        threshold: u32,
+    ) -> StdResult<()> {
+        if let Some(mut recovery) = self.social_recoveries.may_load(storage, owner)? {
+            let approvals = self
+                .social_recovery_approvals
+                .may_load(storage, (owner, &recovery.new_owner))?
+                .unwrap_or_default();
+            if recovery.start_after.is_some() || approvals.len() >= threshold as usize {
+                recovery.approvals = approvals;
+                return self.social_recoveries.save(storage, owner, &recovery);
+            }
+            self.social_recoveries.remove(storage, owner);
+        }
+
+        // the delay starts when a new owner reaches the threshold
+        let approved = self
+            .social_recovery_approvals
+            .prefix(owner)
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .find(|(_, approvals)| approvals.len() >= threshold as usize);
+        if let Some((new_owner, approvals)) = approved {
+            let config = self.recovery_config.load(storage)?;
+            let recovery = SocialRecovery {
+                owner: owner.clone(),
+                new_owner,
+                approvals,
+                executable_at: env.block.time.plus_seconds(config.guardian_delay),
+                start_after: None,
+            };
+            self.social_recoveries.save(storage, owner, &recovery)?;
+        }
+        Ok(())
+    }
+
+    // clear_social_recovery removes the pending social recovery of an owner and every approval of its guardians
+    fn clear_social_recovery(
+        &self,
+        storage: &mut dyn Storage,
+        owner: &Addr,
+    ) -> StdResult<()> {
+        self.social_recoveries.remove(storage, owner);
+        let new_owners = self
+            .social_recovery_approvals
+            .prefix(owner)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for new_owner in &new_owners {
+            self.social_recovery_approvals.remove(storage, (owner, new_owner));
+        }
+        Ok(())
+    }
+
+    // execute_social_recovery is a function that executes a page of a social recovery once it is approved
+    // and its delay has passed. The recovery stays pending until its last page is executed
+    pub fn execute_social_recovery(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        owner: String,
+    ) -> Result<Response, ContractError> {
+        let owner = deps.api.addr_validate(&owner)?;
+        let mut recovery = self
+            .social_recoveries
+            .may_load(deps.storage, &owner)?
+            .ok_or(ContractError::RecoveryNotApproved {})?;
+
+        if env.block.time < recovery.executable_at {
+            return Err(ContractError::RecoveryLocked { executable_at: recovery.executable_at });
+        }
+
+        let rebind = self.rebind_nfts(deps.storage, &env, &info.sender, &recovery.owner, &recovery.new_owner, recovery.start_after.clone())?;
+        if let Some(next) = &rebind.next {
+            recovery.start_after = Some(next.clone());
+            self.social_recoveries.save(deps.storage, &owner, &recovery)?;
+            return Ok(rebind_response("social_recover", &recovery.owner, &recovery.new_owner, rebind));
+        }
+        self.clear_social_recovery(deps.storage, &owner)?;
+
+        // the guardians keep protecting the nfts at the new address
+        if let Some(guardian_set) = self.guardians.may_load(deps.storage, &owner)? {
+            self.guardians.remove(deps.storage, &owner);
+            if !self.guardians.has(deps.storage, &recovery.new_owner) {
+                self.guardians.save(deps.storage, &recovery.new_owner, &guardian_set)?;
+            }
+        }
+
+        // return response
+        Ok(rebind_response("social_recover", &recovery.owner, &recovery.new_owner, rebind))
+    }
+
+    // execute_cancel_social_recovery is a function that allows an owner to cancel the pending social recovery of its nfts
+    pub fn execute_cancel_social_recovery(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        // fail if there is nothing to cancel
+        let approved = self
+            .social_recovery_approvals
+            .prefix(&info.sender)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        if !approved && !self.social_recoveries.has(deps.storage, &info.sender) {
+            return Err(ContractError::NoSocialRecovery {});
+        }
+        self.clear_social_recovery(deps.storage, &info.sender)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "cancel_social_recovery")
+            .add_attribute("owner", info.sender))
+    }
+
+    // execute_set_receive_policy is a function that allows an owner to choose from whom it accepts nfts
+    pub fn execute_set_receive_policy(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        policy: ReceivePolicy,
+    ) -> Result<Response, ContractError> {
+        // store the issuers as validated addresses
+        let validate = |issuers: Vec<String>| {
+            issuers
+                .iter()
+                .map(|issuer| deps.api.addr_validate(issuer).map(|addr| addr.to_string()))
+                .collect::<StdResult<Vec<_>>>()
+        };
+        let policy = match policy {
+            ReceivePolicy::AllowList(issuers) => ReceivePolicy::AllowList(validate(issuers)?),
+            ReceivePolicy::BlockList(issuers) => ReceivePolicy::BlockList(validate(issuers)?),
+            policy => policy,
+        };
+
+        // accepting all nfts is the default
+        if policy == ReceivePolicy::AcceptAll {
+            self.receive_policies.remove(deps.storage, &info.sender);
+        } else {
+            self.receive_policies.save(deps.storage, &info.sender, &policy)?;
+        }
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "set_receive_policy")
+            .add_attribute("owner", info.sender))
+    }
+
+    // rebind_nfts moves a bounded page of the nfts of an owner to a new owner, starting after the given nft,
+    // and returns the events of the moves and where to resume
+    pub fn rebind_nfts(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        sender: &Addr,
+        from: &Addr,
+        to: &Addr,
+        start_after: Option<String>,
+    ) -> Result<Rebind, ContractError> {
+        // collect the nfts first, the owner index changes while they are saved
+        let start = start_after.map(Bound::exclusive);
+        let nfts = self.nfts.idx.owner
+            .prefix(from.to_string())
+            .range(storage, start, None, Order::Ascending)
+            .take(REBIND_LIMIT)
+            .map(|item| item.map(|(_, nft_info)| nft_info))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        // a full page may be followed by another one
+        let next = match nfts.last() {
+            Some(last) if nfts.len() == REBIND_LIMIT => Some(last.id.clone()),
+            _ => None,
+        };
+
+        let mut rebind = Rebind { events: Vec::with_capacity(nfts.len()), hooks: vec![], skipped: vec![], next };
+        for mut nft_info in nfts {
+            // the new owner must not end up with two nfts of a unique kind, the nft stays with the old owner
+            if let Some(kind) = &nft_info.kind {
+                if !nft_info.is_admitted && self.unique_kinds.has(storage, kind) && self.has_badge(storage, to.as_str(), kind, env.block.time)? {
+                    rebind.skipped.push(nft_info.id);
+                    continue;
+                }
+            }
+
+            let old = nft_info.clone();
+            nft_info.owner = to.to_string();
+            self.save_nft(storage, Some(&old), &nft_info, env.block.height)?;
+            let hook_msg = AbtHookMsg::new(AbtEvent::Rebind, &nft_info.id, &nft_info.owner).with_previous_owner(from);
+            rebind.hooks.extend(self.prepare_hook_msgs(storage, hook_msg)?);
+            rebind.events.push(
+                self.transition(storage, env, "rebind", &nft_info, sender, Some(old.status()))?
+                    .add_attribute("previous_owner", from),
+            );
+        }
+
+        Ok(rebind)
+    }
+
+    // execute_update_mint_fee is a function that allows the admin to set the mint fee and the treasury
+    pub fn execute_update_mint_fee(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        fee: Option<Coin>,
+        treasury: String,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        validate_mint_fee(&fee)?;
+        let treasury = deps.api.addr_validate(&treasury)?;
+        self.treasury.save(deps.storage, &treasury)?;
+        match fee {
+            Some(fee) => self.mint_fee.save(deps.storage, &fee)?,
+            None => self.mint_fee.remove(deps.storage),
+        }
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "update_mint_fee")
+            .add_attribute("admin", info.sender)
+            .add_attribute("treasury", treasury))
+    }
+
+    // execute_reindex is a function that allows the admin to reindex the next page of nfts after a migration
+    pub fn execute_reindex(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if self.reindex.may_load(deps.storage)?.is_none() {
+            return Err(ContractError::NoReindex {});
+        }
+        let limit = limit.unwrap_or(DEFAULT_REINDEX_LIMIT).min(MAX_REINDEX_LIMIT) as usize;
+        let (reindexed, next) = self.reindex_nfts(deps.storage, &env, limit)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "reindex")
+            .add_attribute("admin", info.sender)
+            .add_attribute("reindexed", reindexed.to_string())
+            .add_attributes(next.map(|next| ("next", next))))
+    }
+
+    // execute_withdraw is a function that allows the admin to send collected fees to the treasury or another address
+    pub fn execute_withdraw(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        amount: Coin,
+        to: Option<String>,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let to = match to {
+            Some(to) => deps.api.addr_validate(&to)?,
+            None => self.treasury.load(deps.storage)?,
+        };
+
+        // return response
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: to.to_string(),
+                amount: vec![amount.clone()],
+            })
+            .add_attribute("action", "withdraw")
+            .add_attribute("admin", info.sender)
+            .add_attribute("to", to)
+            .add_attribute("amount", amount.to_string()))
+    }
+
+    // execute_add_hook is a function that allows the admin to register a contract notified of nft changes
+    pub fn execute_add_hook(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let hook = deps.api.addr_validate(&addr)?;
+        self.hooks.add_hook(deps.storage, hook)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "add_hook")
+            .add_attribute("admin", info.sender)
+            .add_attribute("hook", addr))
+    }
+
+    // execute_remove_hook is a function that allows the admin to unregister a hook contract
+    pub fn execute_remove_hook(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let hook = deps.api.addr_validate(&addr)?;
+        self.hooks.remove_hook(deps.storage, hook)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "remove_hook")
+            .add_attribute("admin", info.sender)
+            .add_attribute("hook", addr))
+    }
+
+    // execute_update_uri_config is a function that allows the admin to replace the rules for nft uris
+    pub fn execute_update_uri_config(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        config: UriConfig,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        validate_uri_config(&config)?;
+        self.save_uri_config(deps.storage, &config)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "update_uri_config")
+            .add_attribute("admin", info.sender))
+    }
+
+    // execute_create_badge_class is a function that allows the admin to register a new badge class
+    pub fn execute_create_badge_class(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: BadgeClassMsg,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // the class id must be new
+        if msg.id.is_empty() {
+            return Err(ContractError::EmptyBadgeClassId {});
+        }
+        if self.badge_classes.has(deps.storage, &msg.id) {
+            return Err(ContractError::BadgeClassExists { class_id: msg.id });
+        }
+
+        // check the metadata of the class like the metadata of a nft
+        validate_metadata_hash(&msg.metadata_hash)?;
+        let uri_config = self.uri_config.load(deps.storage)?;
+        let (nft_uri, base_uri_version) = self.stored_uri(deps.storage, &uri_config, msg.nft_uri)?;
+        if let Some(expires_after) = msg.expires_after {
+            validate_duration(expires_after)?;
+        }
+
+        let issuers = msg
+            .issuers
+            .iter()
+            .map(|issuer| deps.api.addr_validate(issuer))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let class = BadgeClass {
+            id: msg.id.clone(),
+            nft_uri,
+            base_uri_version,
+            metadata_hash: msg.metadata_hash,
+            max_supply: msg.max_supply,
+            minted: 0,
+            issuers,
+            expires_after: msg.expires_after,
+            kind: msg.kind,
+        };
+        self.badge_classes.save(deps.storage, &msg.id, &class)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "create_badge_class")
+            .add_attribute("class_id", msg.id)
+            .add_attribute("admin", info.sender))
+    }
+
+    // execute_update_unique_kinds is a function that allows the admin to change the kinds of which an owner may hold one nft
+    pub fn execute_update_unique_kinds(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        for kind in &add {
+            self.unique_kinds.save(deps.storage, kind, &Empty {})?;
+        }
+        for kind in &remove {
+            self.unique_kinds.remove(deps.storage, kind);
+        }
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "update_unique_kinds")
+            .add_attribute("admin", info.sender))
+    }
+
+    // execute_update_badge_class_issuers is a function that allows the admin to add or remove issuers of a badge class
+    pub fn execute_update_badge_class_issuers(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        class_id: String,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let mut class = self.badge_classes.load(deps.storage, &class_id)?;
+        for issuer in &add {
+            let issuer = deps.api.addr_validate(issuer)?;
+            if !class.issuers.contains(&issuer) {
+                class.issuers.push(issuer);
+            }
+        }
+        class.issuers.retain(|issuer| !remove.iter().any(|removed| removed == issuer.as_str()));
+        self.badge_classes.save(deps.storage, &class_id, &class)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "update_badge_class_issuers")
+            .add_attribute("class_id", class_id)
+            .add_attribute("admin", info.sender))
+    }
+
+    // execute_update_minter is a function that allows the admin to replace the minter
+    pub fn execute_update_minter(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        minter: String,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let minter = deps.api.addr_validate(&minter)?;
+        self.minter.save(deps.storage, &minter)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "update_minter")
+            .add_attribute("admin", info.sender)
+            .add_attribute("minter", minter))
+    }
+
+    // execute_propose_admin is a function that allows the admin to propose a new admin.
+    // The admin only changes once the proposed one accepts, so a mistyped address cannot lock the contract
+    pub fn execute_propose_admin(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        admin: String,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let current_admin = self.admin.load(deps.storage)?;
+        if info.sender != current_admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let pending_admin = deps.api.addr_validate(&admin)?;
+        self.pending_admin.save(deps.storage, &pending_admin)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "propose_admin")
+            .add_attribute("admin", info.sender)
+            .add_attribute("pending_admin", pending_admin))
+    }
+
+    // execute_accept_admin is a function that allows the proposed admin to take over the administration of the contract
+    pub fn execute_accept_admin(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        // check if the proposed admin is the sender
+        let pending_admin = self.pending_admin.may_load(deps.storage)?.ok_or(ContractError::NoPendingAdmin {})?;
+        if info.sender != pending_admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        self.admin.save(deps.storage, &pending_admin)?;
+        self.pending_admin.remove(deps.storage);
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "accept_admin")
+            .add_attribute("admin", info.sender))
+    }
+
+    // execute_update_contract_info is a function that allows the admin to replace the collection-level metadata
+    pub fn execute_update_contract_info(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: UpdateContractInfoMsg,
+    ) -> Result<Response, ContractError> {
+        // check if the admin is the sender
+        let admin = self.admin.load(deps.storage)?;
+        if info.sender != admin {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // the collection uris must be absolute uris with an allowed scheme
+        let uri_config = self.uri_config.load(deps.storage)?;
+        for uri in [&msg.image, &msg.external_url, &msg.metadata_uri].into_iter().flatten() {
+            validate_absolute_uri(&uri_config, uri)?;
+        }
+
+        // name and symbol are kept, the collection metadata is replaced
+        let mut contract_info = self.contract_info.load(deps.storage)?;
+        contract_info.description = msg.description;
+        contract_info.image = msg.image;
+        contract_info.external_url = msg.external_url;
+        contract_info.issuer_name = msg.issuer_name;
+        contract_info.metadata_uri = msg.metadata_uri;
+
+        self.contract_info.save(deps.storage, &contract_info)?;
+
+        // return response
+        Ok(Response::new()
+            .add_attribute("action", "update_contract_info")
+            .add_attribute("admin", info.sender))
+    }
+
+    // contract_info returns the contract info
+    fn contract_info(&self, deps: Deps) -> StdResult<ContractInfoResponse> {
+        self.contract_info.load(deps.storage)
+    }
+
+    // admin returns the admin of the contract
+    fn admin(&self, deps: Deps) -> StdResult<AdminResponse> {
+        let admin = self.admin.load(deps.storage)?;
+        let pending_admin = self.pending_admin.may_load(deps.storage)?;
+        Ok(AdminResponse {
+            admin: admin.to_string(),
+            pending_admin: pending_admin.map(|pending_admin| pending_admin.to_string()),
+        })
+    }
+
+    // mint_fee returns the fee paid for every minted or claimed nft and the treasury
+    fn mint_fee(&self, deps: Deps) -> StdResult<MintFeeResponse> {
+        Ok(MintFeeResponse {
+            fee: self.mint_fee.may_load(deps.storage)?,
+            treasury: self.treasury.load(deps.storage)?.to_string(),
+        })
+    }
+
+    // num_nfts returns the number of distributed nfts in the contract
+    fn num_nfts(&self, deps: Deps) -> StdResult<NumNftsResponse> {
+        let count = self.nft_count.load(deps.storage)?;
+        Ok(NumNftsResponse { count })
+    }
+
+    // stats returns the number of nfts in each status
+    fn stats(&self, deps: Deps) -> StdResult<StatsResponse> {
+        let counts = self.nft_counts.may_load(deps.storage)?.unwrap_or_default();
+        Ok(StatsResponse {
+            issued: self.nft_count(deps.storage)?,
+            equipped: counts.equipped,
+            unequipped: counts.unequipped,
+            revoked: counts.revoked,
+            burned: self.num_burned.may_load(deps.storage)?.unwrap_or_default(),
+        })
+    }
+
+    // balance_of returns the number of nfts of an owner in each status
+    fn balance_of(&self, deps: Deps, owner: String) -> StdResult<BalanceResponse> {
+        let counts = self.balances.may_load(deps.storage, &owner)?.unwrap_or_default();
+        Ok(balance_response(counts))
+    }
+
+    // balance_at_height returns the number of nfts of an owner in each status at the start of a block
+    fn balance_at_height(&self, deps: Deps, owner: String, height: u64) -> StdResult<BalanceResponse> {
+        let counts = self.balances.may_load_at_height(deps.storage, &owner, height)?.unwrap_or_default();
+        Ok(balance_response(counts))
+    }
+
+    // holders returns the addresses holding at least one nft
+    pub fn holders(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<String>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_deref().map(Bound::exclusive);
+
+        self.balances
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect()
+    }
+
+    // owner_of returns the owner of the token with the given id
+    pub fn owner_of(
+        &self,
+        deps: Deps,
+        nft_id: String,
+    ) -> StdResult<OwnerOfResponse> {
+        let info = self.nfts.load(deps.storage, &nft_id)?;
+        Ok(OwnerOfResponse {
+            owner: info.owner,
+        })
+    }
+
+    // nft_info returns the information of the nft with the given id
+    pub fn nft_info(
+        &self,
+        deps: Deps,
+        nft_id: String,
+    ) -> StdResult<NftInfo> {
+        let info = self.nfts.load(deps.storage, &nft_id)?;
+        self.resolve_nft(deps.storage, info)
+    }
+
+    // nft_infos returns the info of each of the given nfts, or none for ids of no nft
+    pub fn nft_infos(
+        &self,
+        deps: Deps,
+        nft_ids: Vec<String>,
+    ) -> StdResult<Vec<NftInfoEntry>> {
+        if nft_ids.len() > MAX_LIMIT as usize {
+            return Err(StdError::generic_err(format!(
+                "Cannot query more than {} nfts at once",
+                MAX_LIMIT
+            )));
+        }
+
+        nft_ids
+            .into_iter()
+            .map(|nft_id| {
+                let info = self.nfts.may_load(deps.storage, &nft_id)?;
+                let info = info.map(|info| self.resolve_nft(deps.storage, info)).transpose()?;
+                Ok(NftInfoEntry { nft_id, info })
+            })
+            .collect()
+    }
+
+    // nft_history returns the changes of a nft, oldest first
+    pub fn nft_history(
+        &self,
+        deps: Deps,
+        nft_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<HistoryEntry>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        self.history
+            .prefix(&nft_id)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, entry)| entry))
+            .collect()
+    }
+
+    // nft_info_at_height returns the nft as it was at the start of the block at the given height
+    pub fn nft_info_at_height(
+        &self,
+        deps: Deps,
+        nft_id: String,
+        height: u64,
+    ) -> StdResult<Option<NftInfo>> {
+        let info = self.nfts.may_load_at_height(deps.storage, &nft_id, height)?;
+        info.map(|info| self.resolve_nft(deps.storage, info)).transpose()
+    }
+
+    // badge_classes returns the registered badge classes ordered by id
+    pub fn badge_classes(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<BadgeClass>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_deref().map(Bound::exclusive);
+
+        self.badge_classes
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| self.resolve_class(deps.storage, item?.1))
+            .collect()
+    }
+
+    // nfts_of_class returns the nfts minted as assertions of the given badge class
+    pub fn nfts_of_class(
+        &self,
+        deps: Deps,
+        class_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<NftInfo>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        self.nfts.idx.class
+            .prefix(class_id)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| self.resolve_nft(deps.storage, item?.1))
+            .collect()
+    }
+
+    // unique_kinds returns the kinds of which an owner may hold at most one nft
+    pub fn unique_kinds(&self, deps: Deps) -> StdResult<Vec<String>> {
+        self.unique_kinds
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect()
+    }
+
+    // has_badge returns whether the owner holds a nft of the given kind that has not been unadmitted
+    // and has not expired at the given time
+    pub fn has_badge(
+        &self,
+        storage: &dyn Storage,
+        owner: &str,
+        kind: &str,
+        time: Timestamp,
+    ) -> StdResult<bool> {
+        for item in self.nfts.idx.owner_kind
+            .prefix((owner.to_string(), kind.to_string()))
+            .range(storage, None, None, Order::Ascending)
+        {
+            let (_, nft_info) = item?;
+            if !nft_info.is_admitted && !nft_info.is_expired_at(time) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // holds checks whether the valid nfts of an owner meet a requirement
+    pub fn holds(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        requirement: Requirement,
+    ) -> StdResult<HoldsResponse> {
+        if requirement_nodes(&requirement) > MAX_REQUIREMENT_NODES {
+            return Err(StdError::generic_err(format!(
+                "Requirement has more than {} nodes",
+                MAX_REQUIREMENT_NODES
+            )));
+        }
+        validate_requirement(&requirement)?;
+        Ok(HoldsResponse {
+            holds: self.meets_requirement(deps.storage, &env, &owner, &requirement)?,
+        })
+    }
+
+    fn meets_requirement(
+        &self,
+        storage: &dyn Storage,
+        env: &Env,
+        owner: &str,
+        requirement: &Requirement,
+    ) -> StdResult<bool> {
+        match requirement {
+            Requirement::Token { nft_id } => Ok(self.nfts
+                .may_load(storage, nft_id)?
+                .is_some_and(|nft_info| nft_info.owner == owner && nft_info.is_valid_at(env.block.time))),
+            Requirement::Kind { kind } => {
+                for item in self.nfts.idx.owner_kind
+                    .prefix((owner.to_string(), kind.to_string()))
+                    .range(storage, None, None, Order::Ascending)
+                {
+                    let (_, nft_info) = item?;
+                    if nft_info.is_valid_at(env.block.time) {
+                        return Ok(true);
+                    }
                 }
-            });
-        Ok(equipped_nfts)
+                Ok(false)
+            }
+            Requirement::AllOf { requirements } => self.meets_at_least(storage, env, owner, requirements, requirements.len()),
+            Requirement::AnyOf { requirements } => self.meets_at_least(storage, env, owner, requirements, 1),
+            Requirement::NOf { n, requirements } => self.meets_at_least(storage, env, owner, requirements, *n as usize),
+        }
+    }
+
+    // meets_at_least checks whether at least n of the requirements are met, stopping as soon as the answer is known
+    fn meets_at_least(
+        &self,
+        storage: &dyn Storage,
+        env: &Env,
+        owner: &str,
+        requirements: &[Requirement],
+        n: usize,
+    ) -> StdResult<bool> {
+        let mut met = 0;
+        for (checked, requirement) in requirements.iter().enumerate() {
+            if met >= n {
+                break;
+            }
+            // the remaining requirements cannot make up for the missing ones
+            if met + requirements.len() - checked < n {
+                return Ok(false);
+            }
+            if self.meets_requirement(storage, env, owner, requirement)? {
+                met += 1;
+            }
+        }
+        Ok(met >= n)
+    }
+
+    // offers_by_recipient returns the pending offers to a recipient ordered by nft id
+    pub fn offers_by_recipient(
+        &self,
+        deps: Deps,
+        recipient: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Offer>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        self.offers.idx.recipient
+            .prefix(recipient)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| self.resolve_offer(deps.storage, item?.1))
+            .collect()
+    }
+
+    // pending_recovery returns the pending recovery of the given address if any
+    pub fn pending_recovery(
+        &self,
+        deps: Deps,
+        from: String,
+    ) -> StdResult<Option<PendingRecovery>> {
+        let from = deps.api.addr_validate(&from)?;
+        self.pending_recoveries.may_load(deps.storage, &from)
+    }
+
+    // guardian_set returns the guardians of the given owner if any
+    pub fn guardian_set(
+        &self,
+        deps: Deps,
+        owner: String,
+    ) -> StdResult<Option<GuardianSet>> {
+        let owner = deps.api.addr_validate(&owner)?;
+        self.guardians.may_load(deps.storage, &owner)
+    }
+
+    // pending_social_recovery returns the pending social recovery of the given owner if any
+    pub fn pending_social_recovery(
+        &self,
+        deps: Deps,
+        owner: String,
+    ) -> StdResult<Option<SocialRecovery>> {
+        let owner = deps.api.addr_validate(&owner)?;
+        self.social_recoveries.may_load(deps.storage, &owner)
+    }
+
+    // social_recovery_approvals returns the approvals of the guardians of the given owner grouped by new owner
+    pub fn social_recovery_approvals(
+        &self,
+        deps: Deps,
+        owner: String,
+    ) -> StdResult<Vec<SocialRecoveryApprovals>> {
+        let owner = deps.api.addr_validate(&owner)?;
+        self.social_recovery_approvals
+            .prefix(&owner)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(new_owner, guardians)| SocialRecoveryApprovals { new_owner, guardians }))
+            .collect()
+    }
+
+    // receive_policy returns from whom the given owner accepts nfts
+    pub fn receive_policy(
+        &self,
+        deps: Deps,
+        owner: String,
+    ) -> StdResult<ReceivePolicy> {
+        let owner = deps.api.addr_validate(&owner)?;
+        Ok(self.receive_policies.may_load(deps.storage, &owner)?.unwrap_or(ReceivePolicy::AcceptAll))
+    }
+
+    // verify_metadata checks the given content hash against the hash committed at mint time
+    pub fn verify_metadata(
+        &self,
+        deps: Deps,
+        nft_id: String,
+        content_hash: HexBinary,
+    ) -> StdResult<VerifyMetadataResponse> {
+        let info = self.nfts.load(deps.storage, &nft_id)?;
+        Ok(VerifyMetadataResponse {
+            verified: info.metadata_hash == Some(content_hash),
+        })
+    }
+
+    // tokens_by_issuer returns the nfts issued by the given address
+    pub fn tokens_by_issuer(
+        &self,
+        deps: Deps,
+        issuer: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<NftInfo>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        self.nfts.idx.issuer
+            .prefix(issuer)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| self.resolve_nft(deps.storage, item?.1))
+            .collect()
+    }
+
+    // all_unequipped_nft_of is a function that returns all the nfts of a given owner that are unequipped
+    pub fn all_unequipped_nft_of(
+        &self,
+        deps: Deps,
+        owner: String,
+    ) -> StdResult<Vec<NftInfo>> {
+        self.nfts_of_status(deps, owner, NftStatus::Unequipped)
+    }
+
+    // all_equipped_nft_of is a function that returns all the nfts of a given owner that are equipped
+    pub fn all_equipped_nft_of(
+        &self,
+        deps: Deps,
+        owner: String,
+    ) -> StdResult<Vec<NftInfo>> {
+        self.nfts_of_status(deps, owner, NftStatus::Equipped)
+    }
+
+    // nfts_of_status returns the nfts of a given owner with the given status, ranging only over
+    // the matching entries of the (owner, status) index
+    fn nfts_of_status(
+        &self,
+        deps: Deps,
+        owner: String,
+        status: NftStatus,
+    ) -> StdResult<Vec<NftInfo>> {
+        self.nfts.idx.owner_status
+            .prefix((owner, status.as_str().to_string()))
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| self.resolve_nft(deps.storage, item?.1))
+            .collect()
+    }
+
+    // resolve_nft returns the info of a stored nft with its nft uri resolved
+    fn resolve_nft(
+        &self,
+        storage: &dyn Storage,
+        mut info: NftInfo,
+    ) -> StdResult<NftInfo> {
+        info.nft_uri = self.resolve_uri(storage, info.nft_uri, info.base_uri_version.take())?;
+        Ok(info)
+    }
+
+    // resolve_class returns a stored badge class with its nft uri resolved
+    fn resolve_class(
+        &self,
+        storage: &dyn Storage,
+        mut class: BadgeClass,
+    ) -> StdResult<BadgeClass> {
+        class.nft_uri = self.resolve_uri(storage, class.nft_uri, class.base_uri_version.take())?;
+        Ok(class)
+    }
+
+    // resolve_offer returns a stored offer with its nft uri resolved
+    fn resolve_offer(
+        &self,
+        storage: &dyn Storage,
+        mut offer: Offer,
+    ) -> StdResult<Offer> {
+        offer.nft_uri = self.resolve_uri(storage, offer.nft_uri, offer.base_uri_version.take())?;
+        Ok(offer)
+    }
+
+    // resolve_campaign returns a stored campaign with its nft uri resolved
+    fn resolve_campaign(
+        &self,
+        storage: &dyn Storage,
+        mut campaign: Campaign,
+    ) -> StdResult<Campaign> {
+        if let Some(nft_uri) = campaign.nft_uri.take() {
+            campaign.nft_uri = Some(self.resolve_uri(storage, nft_uri, campaign.base_uri_version.take())?);
+        }
+        Ok(campaign)
     }
 }
 
+
+// balance_response returns the response to the balance queries
+fn balance_response(counts: NftCounts) -> BalanceResponse {
+    BalanceResponse {
+        balance: counts.total(),
+        equipped: counts.equipped,
+        unequipped: counts.unequipped,
+        revoked: counts.revoked,
+    }
+}
+
+// rebind_response returns the response to a page of a recovery
+fn rebind_response(action: &str, from: &Addr, to: &Addr, rebind: Rebind) -> Response {
+    let count = rebind.events.len();
+    let mut response = Response::new()
+        .add_submessages(rebind.hooks)
+        .add_events(rebind.events)
+        .add_attribute("action", action)
+        .add_attribute("from", from)
+        .add_attribute("to", to)
+        .add_attribute("count", count.to_string());
+
+    // chains reject empty attribute values
+    if !rebind.skipped.is_empty() {
+        response = response.add_attribute("skipped", rebind.skipped.join(","));
+    }
+    if let Some(next) = rebind.next {
+        response = response.add_attribute("next", next);
+    }
+    response
+}
+
+// requirement_nodes returns the number of nodes of a requirement
+fn requirement_nodes(requirement: &Requirement) -> usize {
+    match requirement {
+        Requirement::Token { .. } | Requirement::Kind { .. } => 1,
+        Requirement::AllOf { requirements }
+        | Requirement::AnyOf { requirements }
+        | Requirement::NOf { requirements, .. } => 1 + requirements.iter().map(requirement_nodes).sum::<usize>(),
+    }
+}
+
+// validate_requirement checks that every combination of a requirement has requirements
+// and that NOf asks for between one and all of them
+fn validate_requirement(requirement: &Requirement) -> StdResult<()> {
+    let (requirements, n) = match requirement {
+        Requirement::Token { .. } | Requirement::Kind { .. } => return Ok(()),
+        Requirement::AllOf { requirements } | Requirement::AnyOf { requirements } => (requirements, None),
+        Requirement::NOf { n, requirements } => (requirements, Some(*n as usize)),
+    };
+    if requirements.is_empty() {
+        return Err(StdError::generic_err("Requirement combines no requirements"));
+    }
+    if let Some(n) = n {
+        if n == 0 || n > requirements.len() {
+            return Err(StdError::generic_err(format!(
+                "NOf requirement needs n between 1 and {}, got {}",
+                requirements.len(),
+                n
+            )));
+        }
+    }
+    requirements.iter().try_for_each(validate_requirement)
+}
+
+// nft_kind checks the kind given for a new nft and returns the one it is stored with.
+// Badges of a class are of the kind of the class, so that issuers of a class cannot mint
+// credentials of another kind. Only the minter mints nfts without class and may set any kind
+fn nft_kind(class: Option<&BadgeClass>, kind: Option<String>) -> Result<Option<String>, ContractError> {
+    match class {
+        Some(class) => {
+            if kind.is_some() && kind != class.kind {
+                return Err(ContractError::BadgeClassKindMismatch { class_id: class.id.clone() });
+            }
+            Ok(class.kind.clone())
+        }
+        None => Ok(kind),
+    }
+}
+
+// validate_metadata_hash checks that a metadata hash is a sha256 digest
+fn validate_metadata_hash(metadata_hash: &Option<HexBinary>) -> Result<(), ContractError> {
+    if let Some(hash) = metadata_hash {
+        if hash.len() != SHA256_LENGTH {
+            return Err(ContractError::InvalidMetadataHash { length: hash.len() });
+        }
+    }
+    Ok(())
+}
+
+// validate_mint_fee checks that a mint fee is not zero, which would still require
+// sending funds of its denom
+fn validate_mint_fee(fee: &Option<Coin>) -> Result<(), ContractError> {
+    if fee.as_ref().is_some_and(|fee| fee.amount.is_zero()) {
+        return Err(ContractError::ZeroMintFee {});
+    }
+    Ok(())
+}
+
+// validate_sha256 checks that a hash is a sha256 digest
+fn validate_sha256(hash: &HexBinary) -> Result<(), ContractError> {
+    if hash.len() != SHA256_LENGTH {
+        return Err(ContractError::InvalidHash { length: hash.len() });
+    }
+    Ok(())
+}
+
+// validate_public_key checks that a public key has the length of a compressed or uncompressed secp256k1 key
+fn validate_public_key(public_key: &HexBinary) -> Result<(), ContractError> {
+    if public_key.len() != 33 && public_key.len() != 65 {
+        return Err(ContractError::InvalidPublicKey { length: public_key.len() });
+    }
+    Ok(())
+}
+
+// hash_fields returns the sha256 of the fields of a merkle leaf or a signed claim. Every field
+// is prefixed with its length as a 4 byte big endian integer, so that no two claims share a hash
+fn hash_fields(fields: &[&str]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for field in fields {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+// verify_merkle_proof checks that the leaf is part of the merkle tree with the given root
+fn verify_merkle_proof(leaf: [u8; 32], proof: &[HexBinary], root: &HexBinary) -> Result<bool, ContractError> {
+    let hash = proof.iter().try_fold(leaf, |hash, node| {
+        let node: [u8; 32] = node.as_slice().try_into().map_err(|_| ContractError::InvalidProof {})?;
+        let mut pair = [hash, node];
+        pair.sort_unstable();
+        Ok::<_, ContractError>(Sha256::digest(pair.concat()).into())
+    })?;
+    Ok(hash.as_slice() == root.as_slice())
+}
+
+// validate_nft_uri checks a nft uri against the configured length limit and schemes
+fn validate_nft_uri(config: &UriConfig, nft_uri: &str) -> Result<(), ContractError> {
+    if nft_uri.is_empty() {
+        return Err(ContractError::EmptyUri {});
+    }
+
+    if nft_uri.len() > config.max_length as usize {
+        return Err(ContractError::UriTooLong {
+            length: nft_uri.len(),
+            max_length: config.max_length,
+        });
+    }
+
+    match uri_scheme(nft_uri) {
+        // absolute uris must use one of the allowed schemes
+        Some(scheme) => check_scheme(config, scheme)?,
+        // relative uris are only meaningful with a base uri
+        None => {
+            if config.base_uri.is_none() {
+                return Err(ContractError::MissingBaseUri {});
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// validate_absolute_uri checks that a uri is within the length limit and uses an allowed scheme
+fn validate_absolute_uri(config: &UriConfig, uri: &str) -> Result<(), ContractError> {
+    if uri.len() > config.max_length as usize {
+        return Err(ContractError::UriTooLong {
+            length: uri.len(),
+            max_length: config.max_length,
+        });
+    }
+
+    match uri_scheme(uri) {
+        Some(scheme) => check_scheme(config, scheme),
+        None => Err(ContractError::NotAbsoluteUri {}),
+    }
+}
+
+// validate_duration checks that a configured duration is not longer than MAX_DURATION
+fn validate_duration(seconds: u64) -> Result<(), ContractError> {
+    if seconds > MAX_DURATION {
+        return Err(ContractError::DurationTooLong { seconds, max_seconds: MAX_DURATION });
+    }
+    Ok(())
+}
+
+// validate_recovery_config checks that the delays of recoveries are not longer than MAX_DURATION
+fn validate_recovery_config(config: &RecoveryConfig) -> Result<(), ContractError> {
+    validate_duration(config.delay)?;
+    validate_duration(config.guardian_delay)
+}
+
+// validate_uri_config checks that the base uri is an absolute uri with an allowed scheme
+fn validate_uri_config(config: &UriConfig) -> Result<(), ContractError> {
+    if let Some(base_uri) = &config.base_uri {
+        validate_absolute_uri(config, base_uri)?;
+    }
+
+    Ok(())
+}
+
+// check_scheme returns an error if the scheme is not in the allowed list
+fn check_scheme(config: &UriConfig, scheme: &str) -> Result<(), ContractError> {
+    if !config.allowed_schemes.iter().any(|allowed| allowed == scheme) {
+        return Err(ContractError::UriSchemeNotAllowed { scheme: scheme.to_string() });
+    }
+    Ok(())
+}
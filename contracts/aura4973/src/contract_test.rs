@@ -1,9 +1,19 @@
 #![cfg(test)]
 // import all requirement to prepare the test environment
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_binary, DepsMut};
+use cosmwasm_std::{attr, coin, coins, from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo, Order, Response, StdError, StdResult, Storage};
+use cw_controllers::HooksResponse;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_storage_plus::{Item, Map};
 
-use crate::state::{Aura4973, NftInfo};
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::ecdsa::{Signature, SigningKey};
+use sha2::{Digest, Sha256};
+
+use crate::hook::{AbtEvent, AbtHookExecuteMsg, AbtHookMsg};
+use crate::receiver::AbtReceiveMsg;
+
+use crate::state::{AdminResponse, Aura4973, BadgeClass, BalanceResponse, HistoryEntry, HoldsResponse, NftInfoEntry, NumHoldersResponse, RevokeIssuedByResponse, Campaign, ContractInfoResponse, GuardianSet, HasBadgeResponse, IsClaimedResponse, NftInfo, NumNftsResponse, Offer, OwnerOfResponse, PendingRecovery, ReceivePolicy, RecoveryConfig, SocialRecovery, SocialRecoveryApprovals, StatsResponse, Tombstone, UriConfig, VerifyMetadataResponse};
 
 const MINTER: &str = "minter";
 
@@ -17,6 +27,11 @@ fn setup_contract(deps: DepsMut) -> Aura4973 {
         name: "Aura 4973".to_string(),
         symbol: "A4973".to_string(),
         minter: String::from(MINTER),
+        admin: None,
+        uri_config: None,
+        recovery_config: None,
+        mint_fee: None,
+        treasury: None,
     };
 
     // call the instantiate function
@@ -31,6 +46,16 @@ fn setup_contract(deps: DepsMut) -> Aura4973 {
 
 }
 
+// function to wrap the contract for cw-multi-test
+fn aura4973_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    );
+    Box::new(contract)
+}
+
 // function for testing mint nft to owner
 #[test]
 fn test_mint_nft() {
@@ -47,11 +72,14 @@ fn test_mint_nft() {
     let env = mock_env();
 
     // prepare the minting message
-    let mint_msg = crate::msg::ExecuteMsg::Mint {
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
         nft_id: "nft_id".to_string(),
         owner: "owner".to_string(),
-        nft_uri: "nft_uri".to_string(),
-    };
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
 
     // a random user cannot mint
     let random = mock_info("random", &[]);
@@ -79,13 +107,13 @@ fn test_mint_nft() {
     let query_res = contract.query(deps.as_ref(), env, query_msg_get_eqquipped_nfts).unwrap();
 
     // convert the query response to the NftInfo type
-    let nft_info: Vec<NftInfo> = from_binary(&query_res).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
 
     // check the nft info
     assert_eq!(nft_info[0].id, "nft_id");
-    assert_eq!(nft_info[0].nft_uri, "nft_uri".to_string());
+    assert_eq!(nft_info[0].nft_uri, "ipfs://nft_uri".to_string());
     assert_eq!(nft_info[0].owner, "owner".to_string());
-    assert_eq!(nft_info[0].equiped, true);
+    assert!(nft_info[0].equiped);
 
 }
 
@@ -105,11 +133,14 @@ fn execute_unequip() {
     let env = mock_env();
 
     // prepare the minting message
-    let mint_msg = crate::msg::ExecuteMsg::Mint {
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
         nft_id: "nft_id".to_string(),
         owner: "owner".to_string(),
-        nft_uri: "nft_uri".to_string(),
-    };
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
 
     // minter mint a nft to owner
     let minter = mock_info(MINTER, &[]);
@@ -140,7 +171,7 @@ fn execute_unequip() {
     let query_res = contract.query(deps.as_ref(), env1, query_msg_get_eqquipped_nfts).unwrap();
 
     // convert the query response to the NftInfo type
-    let nft_info: Vec<NftInfo> = from_binary(&query_res).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
 
     // check the number of nft
     assert_eq!(nft_info.len(), 0);
@@ -157,13 +188,13 @@ fn execute_unequip() {
     let query_res_unequipped = contract.query(deps.as_ref(), env2, query_msg_get_uneqquipped_nfts).unwrap();
 
     // convert the query response to the NftInfo type
-    let nft_info: Vec<NftInfo> = from_binary(&query_res_unequipped).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res_unequipped).unwrap();
 
     // check the nft info
     assert_eq!(nft_info[0].id, "nft_id");
-    assert_eq!(nft_info[0].nft_uri, "nft_uri".to_string());
+    assert_eq!(nft_info[0].nft_uri, "ipfs://nft_uri".to_string());
     assert_eq!(nft_info[0].owner, "owner".to_string());
-    assert_eq!(nft_info[0].equiped, false);
+    assert!(!nft_info[0].equiped);
 
 }
 
@@ -183,11 +214,14 @@ fn execute_re_equip() {
     let env = mock_env();
 
     // prepare the minting message
-    let mint_msg = crate::msg::ExecuteMsg::Mint {
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
         nft_id: "nft_id_re-equip".to_string(),
         owner: "owner".to_string(),
-        nft_uri: "nft_uri".to_string(),
-    };
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
 
     // minter mint a nft to owner
     let minter = mock_info(MINTER, &[]);
@@ -218,7 +252,7 @@ fn execute_re_equip() {
     let query_res = contract.query(deps.as_ref(), env1, query_msg_get_eqquipped_nfts).unwrap();
 
     // convert the query response to the NftInfo type
-    let nft_info: Vec<NftInfo> = from_binary(&query_res).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
 
     // check the number of nft
     assert_eq!(nft_info.len(), 0);
@@ -235,13 +269,13 @@ fn execute_re_equip() {
     let query_res_unequipped = contract.query(deps.as_ref(), env2, query_msg_get_uneqquipped_nfts).unwrap();
 
     // convert the query response to the NftInfo type
-    let nft_info: Vec<NftInfo> = from_binary(&query_res_unequipped).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res_unequipped).unwrap();
 
     // check the nft info
     assert_eq!(nft_info[0].id, "nft_id_re-equip");
-    assert_eq!(nft_info[0].nft_uri, "nft_uri".to_string());
+    assert_eq!(nft_info[0].nft_uri, "ipfs://nft_uri".to_string());
     assert_eq!(nft_info[0].owner, "owner".to_string());
-    assert_eq!(nft_info[0].equiped, false);
+    assert!(!nft_info[0].equiped);
 
     // get id of unequipped nft
     let unequipped_nft_id = &nft_info[0].id;
@@ -269,16 +303,16 @@ fn execute_re_equip() {
     let query_res = contract.query(deps.as_ref(), env1, query_msg_get_eqquipped_nfts).unwrap();
 
     // convert the query response to the NftInfo type
-    let nft_info: Vec<NftInfo> = from_binary(&query_res).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
 
     // check the number of nft
     assert_eq!(nft_info.len(), 1);
 
     // check the nft info
     assert_eq!(nft_info[0].id, "nft_id_re-equip");
-    assert_eq!(nft_info[0].nft_uri, "nft_uri".to_string());
+    assert_eq!(nft_info[0].nft_uri, "ipfs://nft_uri".to_string());
     assert_eq!(nft_info[0].owner, "owner".to_string());
-    assert_eq!(nft_info[0].equiped, true);
+    assert!(nft_info[0].equiped);
 
 }
 
@@ -298,11 +332,14 @@ fn execute_unadmit() {
     let env = mock_env();
 
     // prepare the minting message
-    let mint_msg = crate::msg::ExecuteMsg::Mint {
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
         nft_id: "nft_id_unadmit".to_string(),
         owner: "owner".to_string(),
-        nft_uri: "nft_uri".to_string(),
-    };
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
 
     // minter mint a nft to owner
     let minter = mock_info(MINTER, &[]);
@@ -322,7 +359,7 @@ fn execute_unadmit() {
     let query_res = contract.query(deps.as_ref(), env1, query_msg_get_eqquipped_nfts).unwrap();
 
     // convert the query response to the NftInfo type
-    let nft_info: Vec<NftInfo> = from_binary(&query_res).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
 
     // check the number of nft
     assert_eq!(nft_info.len(), 1);
@@ -350,9 +387,2840 @@ fn execute_unadmit() {
     let query_res = contract.query(deps.as_ref(), env1, query_msg_get_eqquipped_nfts).unwrap();
 
     // convert the query response to the NftInfo type
-    let nft_info: Vec<NftInfo> = from_binary(&query_res).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
 
     // check the number of nft
     assert_eq!(nft_info.len(), 0);
 
-}
\ No newline at end of file
+}
+// function to test the metadata hash committed at mint time
+#[test]
+fn test_verify_metadata() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let env = mock_env();
+    let minter = mock_info(MINTER, &[]);
+
+    // a hash that is not 32 bytes long is rejected
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id_hashed".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: Some(HexBinary::from(vec![1u8; 20])),
+        class_id: None,
+        kind: None,
+    });
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::InvalidMetadataHash { length: 20 });
+
+    // minter mint a nft with a sha256 commitment
+    let hash = HexBinary::from(vec![7u8; 32]);
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id_hashed".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: Some(hash.clone()),
+        class_id: None,
+        kind: None,
+    });
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg)
+        .unwrap();
+
+    // the committed hash verifies
+    let query_msg = crate::msg::QueryMsg::VerifyMetadata {
+        nft_id: "nft_id_hashed".to_string(),
+        content_hash: hash,
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let res: VerifyMetadataResponse = from_json(&query_res).unwrap();
+    assert!(res.verified);
+
+    // any other content does not
+    let query_msg = crate::msg::QueryMsg::VerifyMetadata {
+        nft_id: "nft_id_hashed".to_string(),
+        content_hash: HexBinary::from(vec![8u8; 32]),
+    };
+    let query_res = contract.query(deps.as_ref(), env, query_msg).unwrap();
+    let res: VerifyMetadataResponse = from_json(&query_res).unwrap();
+    assert!(!res.verified);
+
+}
+
+// function to test the validation and resolution of nft uris
+#[test]
+fn test_nft_uri_validation() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let env = mock_env();
+    let minter = mock_info(MINTER, &[]);
+
+    // helper to build a minting message with the given uri
+    let mint_msg = |nft_uri: &str| crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id_uri".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some(nft_uri.to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+
+    // empty, oversized, disallowed and relative uris are rejected
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg(""))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::EmptyUri {});
+
+    let long_uri = format!("ipfs://{}", "a".repeat(600));
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg(&long_uri))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::UriTooLong { length: 607, max_length: 512 });
+
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("http://example.com/1.json"))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::UriSchemeNotAllowed { scheme: "http".to_string() });
+
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("1.json"))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::MissingBaseUri {});
+
+    // only the admin can set a base uri
+    let update_msg = crate::msg::ExecuteMsg::UpdateUriConfig {
+        config: UriConfig {
+            base_uri: Some("ipfs://QmBase/".to_string()),
+            ..UriConfig::default()
+        },
+    };
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), random, update_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+
+    // the minter is the admin by default
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), update_msg)
+        .unwrap();
+
+    // a relative uri is now accepted and resolved against the base uri
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg("1.json"))
+        .unwrap();
+
+    let query_msg = crate::msg::QueryMsg::NftInfo {
+        nft_id: "nft_id_uri".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let nft_info: NftInfo = from_json(&query_res).unwrap();
+    assert_eq!(nft_info.nft_uri, "ipfs://QmBase/1.json".to_string());
+    assert_eq!(nft_info.base_uri_version, None);
+
+    // only the relative path is stored, along with the version of the base uri
+    let stored = contract.nfts.load(deps.as_ref().storage, "nft_id_uri").unwrap();
+    assert_eq!(stored.nft_uri, "1.json".to_string());
+    assert_eq!(stored.base_uri_version, Some(0));
+
+    // changing or removing the base uri does not point the nft elsewhere
+    let update_msg = crate::msg::ExecuteMsg::UpdateUriConfig {
+        config: UriConfig {
+            base_uri: Some("ipfs://QmOther/".to_string()),
+            ..UriConfig::default()
+        },
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), update_msg)
+        .unwrap();
+    let update_msg = crate::msg::ExecuteMsg::UpdateUriConfig {
+        config: UriConfig::default(),
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), update_msg)
+        .unwrap();
+
+    let query_res = contract.query(deps.as_ref(), env, query_msg).unwrap();
+    let nft_info: NftInfo = from_json(&query_res).unwrap();
+    assert_eq!(nft_info.nft_uri, "ipfs://QmBase/1.json".to_string());
+
+    // each base uri is kept as a new version
+    assert_eq!(contract.base_uris.load(deps.as_ref().storage, 0).unwrap(), "ipfs://QmBase/".to_string());
+    assert_eq!(contract.base_uris.load(deps.as_ref().storage, 1).unwrap(), "ipfs://QmOther/".to_string());
+
+}
+
+// function to test the admin can update the collection-level metadata
+#[test]
+fn test_update_contract_info() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let env = mock_env();
+
+    // prepare the update message
+    let update_msg = crate::msg::ExecuteMsg::UpdateContractInfo(crate::msg::UpdateContractInfoMsg {
+        description: Some("Course completion certificates".to_string()),
+        image: Some("ipfs://QmLogo".to_string()),
+        external_url: Some("https://aura.network".to_string()),
+        issuer_name: Some("Aura Academy".to_string()),
+        metadata_uri: Some("ipfs://QmCollection".to_string()),
+    });
+
+    // a random user cannot update the contract info
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), random, update_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+
+    // a relative image uri is rejected
+    let minter = mock_info(MINTER, &[]);
+    let invalid_msg = crate::msg::ExecuteMsg::UpdateContractInfo(crate::msg::UpdateContractInfoMsg {
+        description: None,
+        image: Some("logo.png".to_string()),
+        external_url: None,
+        issuer_name: None,
+        metadata_uri: None,
+    });
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), invalid_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NotAbsoluteUri {});
+
+    // the admin updates the contract info
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter, update_msg)
+        .unwrap();
+
+    // name and symbol are kept, the collection metadata is replaced
+    let query_res = contract.query(deps.as_ref(), env, crate::msg::QueryMsg::ContractInfo {}).unwrap();
+    let info: ContractInfoResponse = from_json(&query_res).unwrap();
+    assert_eq!(info.name, "Aura 4973".to_string());
+    assert_eq!(info.symbol, "A4973".to_string());
+    assert_eq!(info.description, Some("Course completion certificates".to_string()));
+    assert_eq!(info.image, Some("ipfs://QmLogo".to_string()));
+    assert_eq!(info.external_url, Some("https://aura.network".to_string()));
+    assert_eq!(info.issuer_name, Some("Aura Academy".to_string()));
+    assert_eq!(info.metadata_uri, Some("ipfs://QmCollection".to_string()));
+
+}
+
+// function to test minting badges of a badge class
+#[test]
+fn test_badge_class() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let env = mock_env();
+    let minter = mock_info(MINTER, &[]);
+
+    // prepare the badge class message
+    let create_msg = crate::msg::ExecuteMsg::CreateBadgeClass(crate::msg::BadgeClassMsg {
+        id: "course_x".to_string(),
+        nft_uri: "ipfs://course_x".to_string(),
+        metadata_hash: None,
+        max_supply: Some(1),
+        issuers: vec!["teacher".to_string()],
+        expires_after: Some(3600),
+        kind: Some("course".to_string()),
+    });
+
+    // a random user cannot create a badge class
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), random.clone(), create_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+
+    // badges cannot expire so late that their expiry overflows
+    let mut endless_msg = create_msg.clone();
+    if let crate::msg::ExecuteMsg::CreateBadgeClass(msg) = &mut endless_msg {
+        msg.expires_after = Some(u64::MAX);
+    }
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), endless_msg)
+        .unwrap_err();
+    assert!(matches!(err, crate::error::ContractError::DurationTooLong { seconds: u64::MAX, .. }));
+
+    // the admin creates the badge class
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter, create_msg)
+        .unwrap();
+
+    // helpers to build a minting message of the badge class
+    let mint_msg_of_class = |nft_id: &str| crate::msg::MintMsg {
+        nft_id: nft_id.to_string(),
+        owner: "owner".to_string(),
+        nft_uri: None,
+        metadata_hash: None,
+        class_id: Some("course_x".to_string()),
+        kind: None,
+    };
+    let mint_msg = |nft_id: &str| crate::msg::ExecuteMsg::Mint(mint_msg_of_class(nft_id));
+
+    // a random user is not an issuer of the class
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), random, mint_msg("badge_1"))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NotMinter {});
+
+    // a badge of the class cannot carry other metadata than the class
+    let teacher = mock_info("teacher", &[]);
+    let mismatch_msgs = [
+        crate::msg::MintMsg {
+            nft_uri: Some("ipfs://other".to_string()),
+            metadata_hash: None,
+            ..mint_msg_of_class("badge_1")
+        },
+        crate::msg::MintMsg {
+            nft_uri: None,
+            metadata_hash: Some(HexBinary::from(vec![7u8; 32])),
+            ..mint_msg_of_class("badge_1")
+        },
+    ];
+    for msg in mismatch_msgs {
+        let err = contract
+            .execute(deps.as_mut(), env.clone(), teacher.clone(), crate::msg::ExecuteMsg::Mint(msg))
+            .unwrap_err();
+        assert_eq!(err, crate::error::ContractError::BadgeClassMetadataMismatch { class_id: "course_x".to_string() });
+    }
+
+    // an issuer of the class mints a badge which carries the class metadata
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), teacher.clone(), mint_msg("badge_1"))
+        .unwrap();
+
+    // the supply of the class is exhausted
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), teacher, mint_msg("badge_2"))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::BadgeClassSupplyExhausted { class_id: "course_x".to_string() });
+
+    // check the badge class
+    let query_msg = crate::msg::QueryMsg::BadgeClass {
+        class_id: "course_x".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let class: BadgeClass = from_json(&query_res).unwrap();
+    assert_eq!(class.minted, 1);
+
+    // check the nfts of the class
+    let query_msg = crate::msg::QueryMsg::NftsOfClass {
+        class_id: "course_x".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
+    assert_eq!(nft_info.len(), 1);
+    assert_eq!(nft_info[0].id, "badge_1");
+    assert_eq!(nft_info[0].nft_uri, "ipfs://course_x".to_string());
+    assert_eq!(nft_info[0].class_id, Some("course_x".to_string()));
+    assert_eq!(nft_info[0].expires_at, Some(env.block.time.plus_seconds(3600)));
+
+    // the badge is held until it expires
+    let query_msg = crate::msg::QueryMsg::HasBadge {
+        owner: "owner".to_string(),
+        kind: "course".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let res: HasBadgeResponse = from_json(&query_res).unwrap();
+    assert!(res.has_badge);
+
+    let mut expired_env = env;
+    expired_env.block.time = expired_env.block.time.plus_seconds(3600);
+    let query_res = contract.query(deps.as_ref(), expired_env, query_msg).unwrap();
+    let res: HasBadgeResponse = from_json(&query_res).unwrap();
+    assert!(!res.has_badge);
+
+}
+
+// function to test an owner holds at most one nft of a unique kind
+#[test]
+fn test_unique_kind() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let env = mock_env();
+    let minter = mock_info(MINTER, &[]);
+
+    // the admin makes the membership kind unique
+    let update_msg = crate::msg::ExecuteMsg::UpdateUniqueKinds {
+        add: vec!["membership".to_string()],
+        remove: vec![],
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), update_msg)
+        .unwrap();
+
+    // helper to build a minting message of the given kind
+    let mint_msg = |nft_id: &str, kind: &str| crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: nft_id.to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: Some(kind.to_string()),
+    });
+
+    // the owner does not hold a membership yet
+    let query_msg = crate::msg::QueryMsg::HasBadge {
+        owner: "owner".to_string(),
+        kind: "membership".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let res: HasBadgeResponse = from_json(&query_res).unwrap();
+    assert!(!res.has_badge);
+
+    // minter mint a membership to owner
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("member_1", "membership"))
+        .unwrap();
+
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let res: HasBadgeResponse = from_json(&query_res).unwrap();
+    assert!(res.has_badge);
+
+    // a second membership is rejected
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("member_2", "membership"))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::AlreadyHoldsBadge { kind: "membership".to_string() });
+
+    // kinds that are not unique can be held many times
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("attendance_1", "attendance"))
+        .unwrap();
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("attendance_2", "attendance"))
+        .unwrap();
+
+    // issuers of a badge class cannot mint a unique kind through the class
+    let create_msg = crate::msg::ExecuteMsg::CreateBadgeClass(crate::msg::BadgeClassMsg {
+        id: "course_x".to_string(),
+        nft_uri: "ipfs://course_x".to_string(),
+        metadata_hash: None,
+        max_supply: None,
+        issuers: vec!["teacher".to_string()],
+        expires_after: None,
+        kind: Some("course".to_string()),
+    });
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), create_msg)
+        .unwrap();
+    let class_mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "fake_member".to_string(),
+        owner: "other".to_string(),
+        nft_uri: None,
+        metadata_hash: None,
+        class_id: Some("course_x".to_string()),
+        kind: Some("membership".to_string()),
+    });
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("teacher", &[]), class_mint_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::BadgeClassKindMismatch { class_id: "course_x".to_string() });
+
+    // without a class only the minter may mint, so the teacher cannot set a kind either
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("teacher", &[]), mint_msg("fake_member", "membership"))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NotMinter {});
+
+    let query_msg = crate::msg::QueryMsg::HasBadge {
+        owner: "other".to_string(),
+        kind: "membership".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let res: HasBadgeResponse = from_json(&query_res).unwrap();
+    assert!(!res.has_badge);
+
+    // once the membership is unadmitted a new one can be minted
+    let unadmit_msg = crate::msg::ExecuteMsg::UnAdmit {
+        nft_id: "member_1".to_string(),
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), unadmit_msg)
+        .unwrap();
+    let _res = contract
+        .execute(deps.as_mut(), env, minter, mint_msg("member_2", "membership"))
+        .unwrap();
+
+}
+
+// function to test cw721 transfer and approval messages are rejected
+#[test]
+fn test_soulbound() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let env = mock_env();
+
+    // minter mint a nft to owner
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+    let minter = mock_info(MINTER, &[]);
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg)
+        .unwrap();
+
+    // take a snapshot of the whole storage
+    let before: Vec<_> = deps.storage.range(None, None, Order::Ascending).collect();
+
+    // the owner sends every cw721 message a wallet could send
+    let messages = vec![
+        ("transfer_nft", crate::msg::ExecuteMsg::TransferNft {
+            recipient: "recipient".to_string(),
+            token_id: "nft_id".to_string(),
+        }),
+        ("send_nft", crate::msg::ExecuteMsg::SendNft {
+            contract: "contract".to_string(),
+            token_id: "nft_id".to_string(),
+            msg: Binary::default(),
+        }),
+        ("approve", crate::msg::ExecuteMsg::Approve {
+            spender: "spender".to_string(),
+            token_id: "nft_id".to_string(),
+            expires: None,
+        }),
+        ("revoke", crate::msg::ExecuteMsg::Revoke {
+            spender: "spender".to_string(),
+            token_id: "nft_id".to_string(),
+        }),
+        ("approve_all", crate::msg::ExecuteMsg::ApproveAll {
+            operator: "operator".to_string(),
+            expires: None,
+        }),
+        ("revoke_all", crate::msg::ExecuteMsg::RevokeAll {
+            operator: "operator".to_string(),
+        }),
+    ];
+    let owner = mock_info("owner", &[]);
+    for (action, msg) in messages {
+        let err = contract
+            .execute(deps.as_mut(), env.clone(), owner.clone(), msg)
+            .unwrap_err();
+        assert_eq!(err, crate::error::ContractError::Soulbound { action: action.to_string() });
+    }
+
+    // no state has changed
+    let after: Vec<_> = deps.storage.range(None, None, Order::Ascending).collect();
+    assert_eq!(before, after);
+
+    // the cw721 json of a transfer is understood
+    let msg: crate::msg::ExecuteMsg = from_json(br#"{"transfer_nft":{"recipient":"recipient","token_id":"nft_id"}}"#).unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env, owner, msg)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Soulbound token: transfer_nft is not supported");
+
+}
+
+// function to test the minter can recover the nfts of a lost address
+#[test]
+fn test_recover() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let mut env = mock_env();
+    let minter = mock_info(MINTER, &[]);
+
+    // minter mint two nfts to owner
+    for nft_id in ["nft_id_1", "nft_id_2"] {
+        let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+            nft_id: nft_id.to_string(),
+            owner: "owner".to_string(),
+            nft_uri: Some("ipfs://nft_uri".to_string()),
+            metadata_hash: None,
+            class_id: None,
+            kind: None,
+        });
+        let _res = contract
+            .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // delays long enough to overflow the execution time are rejected
+    for (delay, guardian_delay) in [(u64::MAX, 3600), (3600, u64::MAX)] {
+        let config_msg = crate::msg::ExecuteMsg::UpdateRecoveryConfig {
+            config: RecoveryConfig { delay, require_confirmation: true, guardian_delay },
+        };
+        let err = contract
+            .execute(deps.as_mut(), env.clone(), minter.clone(), config_msg)
+            .unwrap_err();
+        assert!(matches!(err, crate::error::ContractError::DurationTooLong { seconds: u64::MAX, .. }));
+    }
+
+    // the admin requires a one hour delay and a confirmation
+    let config_msg = crate::msg::ExecuteMsg::UpdateRecoveryConfig {
+        config: RecoveryConfig {
+            delay: 3600,
+            require_confirmation: true,
+            guardian_delay: 3600,
+        },
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), config_msg)
+        .unwrap();
+
+    // only the minter can request a recovery
+    let recover_msg = crate::msg::ExecuteMsg::Recover {
+        from: "owner".to_string(),
+        to: "new_owner".to_string(),
+    };
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), random, recover_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NotMinter {});
+
+    // the old owner cancels the first request
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), recover_msg.clone())
+        .unwrap();
+    let owner = mock_info("owner", &[]);
+    let cancel_msg = crate::msg::ExecuteMsg::CancelRecovery {
+        from: "owner".to_string(),
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), owner, cancel_msg)
+        .unwrap();
+
+    // the minter requests the recovery again
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter, recover_msg)
+        .unwrap();
+    let query_msg = crate::msg::QueryMsg::PendingRecovery {
+        from: "owner".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let recovery: Option<PendingRecovery> = from_json(&query_res).unwrap();
+    assert_eq!(recovery.unwrap().executable_at, env.block.time.plus_seconds(3600));
+
+    // the recovery is locked during the delay
+    let finalize_msg = crate::msg::ExecuteMsg::FinalizeRecovery {
+        from: "owner".to_string(),
+    };
+    let new_owner = mock_info("new_owner", &[]);
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), new_owner.clone(), finalize_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::RecoveryLocked { executable_at: env.block.time.plus_seconds(3600) });
+
+    // the recovery must be confirmed by the new owner
+    env.block.time = env.block.time.plus_seconds(3600);
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), new_owner.clone(), finalize_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::RecoveryNotConfirmed {});
+
+    let confirm_msg = crate::msg::ExecuteMsg::ConfirmRecovery {
+        from: "owner".to_string(),
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), new_owner.clone(), confirm_msg)
+        .unwrap();
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), new_owner, finalize_msg)
+        .unwrap();
+
+    // the nfts are bound to the new owner
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::OwnerOf { nft_id: "nft_id_1".to_string() }).unwrap();
+    let res: OwnerOfResponse = from_json(&query_res).unwrap();
+    assert_eq!(res.owner, "new_owner".to_string());
+
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::AllEquippedNftOf { owner: "new_owner".to_string() }).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
+    assert_eq!(nft_info.len(), 2);
+
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::AllEquippedNftOf { owner: "owner".to_string() }).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
+    assert_eq!(nft_info.len(), 0);
+
+    // the recovery is no longer pending
+    let query_res = contract.query(deps.as_ref(), env, query_msg).unwrap();
+    let recovery: Option<PendingRecovery> = from_json(&query_res).unwrap();
+    assert_eq!(recovery, None);
+
+}
+
+// function to test large recoveries are executed in pages and skip conflicting nfts
+#[test]
+fn test_recover_pages() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let env = mock_env();
+    let minter = mock_info(MINTER, &[]);
+
+    // recoveries are executed right away
+    let config_msg = crate::msg::ExecuteMsg::UpdateRecoveryConfig {
+        config: RecoveryConfig {
+            delay: 0,
+            require_confirmation: false,
+            guardian_delay: 0,
+        },
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), config_msg)
+        .unwrap();
+    let update_msg = crate::msg::ExecuteMsg::UpdateUniqueKinds {
+        add: vec!["membership".to_string()],
+        remove: vec![],
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), update_msg)
+        .unwrap();
+
+    // both addresses hold a membership, the lost one holds 34 more nfts
+    let mut mints = vec![
+        ("lost_member".to_string(), "lost", Some("membership".to_string())),
+        ("new_member".to_string(), "new_owner", Some("membership".to_string())),
+    ];
+    mints.extend((0..34).map(|i| (format!("nft_id_{:02}", i), "lost", None)));
+    for (nft_id, owner, kind) in mints {
+        let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+            nft_id,
+            owner: owner.to_string(),
+            nft_uri: Some("ipfs://nft_uri".to_string()),
+            metadata_hash: None,
+            class_id: None,
+            kind,
+        });
+        let _res = contract
+            .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // the first page skips the membership and stops after 30 nfts
+    let recover_msg = crate::msg::ExecuteMsg::Recover {
+        from: "lost".to_string(),
+        to: "new_owner".to_string(),
+    };
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), minter, recover_msg)
+        .unwrap();
+    assert!(res.attributes.contains(&attr("count", "29")));
+    assert!(res.attributes.contains(&attr("skipped", "lost_member")));
+    assert!(res.attributes.contains(&attr("next", "nft_id_28")));
+
+    // the recovery stays pending until its last page
+    let query_msg = crate::msg::QueryMsg::PendingRecovery {
+        from: "lost".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let recovery: Option<PendingRecovery> = from_json(&query_res).unwrap();
+    assert_eq!(recovery.unwrap().start_after, Some("nft_id_28".to_string()));
+
+    let finalize_msg = crate::msg::ExecuteMsg::FinalizeRecovery {
+        from: "lost".to_string(),
+    };
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("random", &[]), finalize_msg)
+        .unwrap();
+    assert!(res.attributes.contains(&attr("count", "5")));
+    assert!(!res.attributes.iter().any(|attr| attr.key == "skipped" || attr.key == "next"));
+
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let recovery: Option<PendingRecovery> = from_json(&query_res).unwrap();
+    assert_eq!(recovery, None);
+
+    // every nft moved but the skipped membership
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::BalanceOf { owner: "new_owner".to_string() }).unwrap();
+    let balance: BalanceResponse = from_json(&query_res).unwrap();
+    assert_eq!(balance.balance, 35);
+    let query_res = contract.query(deps.as_ref(), env, crate::msg::QueryMsg::OwnerOf { nft_id: "lost_member".to_string() }).unwrap();
+    let res: OwnerOfResponse = from_json(&query_res).unwrap();
+    assert_eq!(res.owner, "lost".to_string());
+
+}
+
+// function to test guardians can jointly recover the nfts of an owner
+#[test]
+fn test_social_recovery() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let mut env = mock_env();
+
+    // minter mint a nft to owner
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+    let minter = mock_info(MINTER, &[]);
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg)
+        .unwrap();
+
+    // the owner designates three guardians, two of them must approve
+    let owner = mock_info("owner", &[]);
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), owner.clone(), crate::msg::ExecuteMsg::SetGuardians {
+            guardians: vec!["alice".to_string(), "bob".to_string()],
+            threshold: 3,
+        })
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::InvalidThreshold {});
+
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), owner, crate::msg::ExecuteMsg::SetGuardians {
+            guardians: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+            threshold: 2,
+        })
+        .unwrap();
+
+    // prepare the approval message
+    let approve_msg = crate::msg::ExecuteMsg::ApproveSocialRecovery {
+        owner: "owner".to_string(),
+        new_owner: "new_owner".to_string(),
+    };
+
+    // a random user is not a guardian
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), random, approve_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NotGuardian {});
+
+    // one approval is not enough
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), approve_msg.clone())
+        .unwrap();
+    let execute_msg = crate::msg::ExecuteMsg::ExecuteSocialRecovery {
+        owner: "owner".to_string(),
+    };
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), execute_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::RecoveryNotApproved {});
+
+    // a guardian proposing another address does not block the others
+    let bad_approve_msg = crate::msg::ExecuteMsg::ApproveSocialRecovery {
+        owner: "owner".to_string(),
+        new_owner: "attacker".to_string(),
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), bad_approve_msg.clone())
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), execute_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::RecoveryNotApproved {});
+
+    let query_msg = crate::msg::QueryMsg::SocialRecoveryApprovals {
+        owner: "owner".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let approvals: Vec<SocialRecoveryApprovals> = from_json(&query_res).unwrap();
+    assert_eq!(approvals, vec![
+        SocialRecoveryApprovals { new_owner: Addr::unchecked("attacker"), guardians: vec![Addr::unchecked("carol")] },
+        SocialRecoveryApprovals { new_owner: Addr::unchecked("new_owner"), guardians: vec![Addr::unchecked("alice")] },
+    ]);
+
+    // the second approval of the new owner starts the delay
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), approve_msg.clone())
+        .unwrap();
+    let query_msg = crate::msg::QueryMsg::PendingSocialRecovery {
+        owner: "owner".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let recovery: Option<SocialRecovery> = from_json(&query_res).unwrap();
+    let recovery = recovery.unwrap();
+    assert_eq!(recovery.new_owner, Addr::unchecked("new_owner"));
+    let executable_at = env.block.time.plus_seconds(3 * 24 * 60 * 60);
+    assert_eq!(recovery.executable_at, executable_at);
+
+    // a withdrawn approval stops the recovery until the threshold is reached again
+    let withdraw_msg = crate::msg::ExecuteMsg::WithdrawSocialRecoveryApproval {
+        owner: "owner".to_string(),
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), withdraw_msg.clone())
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), withdraw_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NoApproval {});
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let recovery: Option<SocialRecovery> = from_json(&query_res).unwrap();
+    assert_eq!(recovery, None);
+
+    // the guardian that proposed another address switches its approval
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), bad_approve_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::AlreadyApproved {});
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), approve_msg)
+        .unwrap();
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let recovery: Option<SocialRecovery> = from_json(&query_res).unwrap();
+    let recovery = recovery.unwrap();
+    assert_eq!(recovery.approvals, vec![Addr::unchecked("alice"), Addr::unchecked("carol")]);
+    assert_eq!(recovery.executable_at, executable_at);
+
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), execute_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::RecoveryLocked { executable_at });
+
+    // after the delay the nft is bound to the new owner
+    env.block.time = executable_at;
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), execute_msg)
+        .unwrap();
+
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::OwnerOf { nft_id: "nft_id".to_string() }).unwrap();
+    let res: OwnerOfResponse = from_json(&query_res).unwrap();
+    assert_eq!(res.owner, "new_owner".to_string());
+
+    // the guardians now protect the new owner
+    let query_msg = crate::msg::QueryMsg::Guardians {
+        owner: "new_owner".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env, query_msg).unwrap();
+    let guardian_set: Option<GuardianSet> = from_json(&query_res).unwrap();
+    assert_eq!(guardian_set.unwrap().threshold, 2);
+
+}
+
+// function to test owners can refuse nfts from issuers
+#[test]
+fn test_receive_policy() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let env = mock_env();
+    let minter = mock_info(MINTER, &[]);
+    let owner = mock_info("owner", &[]);
+
+    // helper to build a minting message to owner
+    let mint_msg = |nft_id: &str| crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: nft_id.to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+
+    // helper to set the policy of owner
+    let policy_msg = |policy: ReceivePolicy| crate::msg::ExecuteMsg::SetReceivePolicy { policy };
+
+    // the owner blocks the minter
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), owner.clone(), policy_msg(ReceivePolicy::BlockList(vec![MINTER.to_string()])))
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("nft_id_1"))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::RecipientRefused { issuer: MINTER.to_string() });
+
+    // the owner only accepts nfts it claims itself
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), owner.clone(), policy_msg(ReceivePolicy::RequireClaim))
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("nft_id_1"))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::ClaimRequired {});
+
+    // the owner allows the minter
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), owner, policy_msg(ReceivePolicy::AllowList(vec![MINTER.to_string()])))
+        .unwrap();
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg("nft_id_1"))
+        .unwrap();
+
+    // check the policy of owner
+    let query_msg = crate::msg::QueryMsg::ReceivePolicy {
+        owner: "owner".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env, query_msg).unwrap();
+    let policy: ReceivePolicy = from_json(&query_res).unwrap();
+    assert_eq!(policy, ReceivePolicy::AllowList(vec![MINTER.to_string()]));
+
+}
+
+// function to test recipients accept or reject offered nfts
+#[test]
+fn test_offer() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let env = mock_env();
+    let minter = mock_info(MINTER, &[]);
+    let owner = mock_info("owner", &[]);
+
+    // helper to build an offer to owner
+    let offer_msg = |nft_id: &str, expires: Option<cw_utils::Expiration>| crate::msg::ExecuteMsg::Offer(crate::msg::OfferMsg {
+        nft_id: nft_id.to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+        expires,
+    });
+
+    // a random user cannot make offers
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), random.clone(), offer_msg("nft_id_1", None))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NotMinter {});
+
+    // the minter offers three nfts, one of them expires right away
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), offer_msg("nft_id_1", None))
+        .unwrap();
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), offer_msg("nft_id_2", None))
+        .unwrap();
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), offer_msg("nft_id_3", Some(cw_utils::Expiration::AtHeight(env.block.height))))
+        .unwrap();
+
+    // the same id cannot be offered twice, nor minted while it is offered
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), offer_msg("nft_id_1", None))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NftAlreadyExists { nft_id: "nft_id_1".to_string() });
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id_1".to_string(),
+        owner: "random".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NftAlreadyExists { nft_id: "nft_id_1".to_string() });
+
+    // the metadata is checked when offering
+    let mut invalid_offer = offer_msg("nft_id_4", None);
+    if let crate::msg::ExecuteMsg::Offer(msg) = &mut invalid_offer {
+        msg.nft_uri = Some("ftp://nft_uri".to_string());
+    }
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), invalid_offer)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::UriSchemeNotAllowed { scheme: "ftp".to_string() });
+
+    // only the issuer can cancel an offer
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), offer_msg("nft_id_4", None))
+        .unwrap();
+    let cancel_msg = crate::msg::ExecuteMsg::CancelOffer {
+        nft_id: "nft_id_4".to_string(),
+    };
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), random.clone(), cancel_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), cancel_msg)
+        .unwrap();
+
+    // check the offers to owner
+    let query_msg = crate::msg::QueryMsg::OffersByRecipient {
+        recipient: "owner".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let offers: Vec<Offer> = from_json(&query_res).unwrap();
+    assert_eq!(offers.len(), 3);
+
+    // nothing is minted before the offers are accepted
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::NumNfts {}).unwrap();
+    let res: NumNftsResponse = from_json(&query_res).unwrap();
+    assert_eq!(res.count, 0);
+
+    // only the recipient can accept an offer
+    let accept_msg = crate::msg::ExecuteMsg::Accept {
+        nft_id: "nft_id_1".to_string(),
+    };
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), random, accept_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+
+    // the owner accepts the first offer, rejects the second and is too late for the third
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), owner.clone(), accept_msg)
+        .unwrap();
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), owner.clone(), crate::msg::ExecuteMsg::Reject { nft_id: "nft_id_2".to_string() })
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), owner, crate::msg::ExecuteMsg::Accept { nft_id: "nft_id_3".to_string() })
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::OfferExpired {});
+
+    // only the accepted offer is minted
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::NumNfts {}).unwrap();
+    let res: NumNftsResponse = from_json(&query_res).unwrap();
+    assert_eq!(res.count, 1);
+
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::AllEquippedNftOf { owner: "owner".to_string() }).unwrap();
+    let nft_info: Vec<NftInfo> = from_json(&query_res).unwrap();
+    assert_eq!(nft_info.len(), 1);
+    assert_eq!(nft_info[0].id, "nft_id_1");
+
+    // the expired offer is still pending
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let offers: Vec<Offer> = from_json(&query_res).unwrap();
+    assert_eq!(offers.len(), 1);
+    assert_eq!(offers[0].nft_id, "nft_id_3");
+    assert_eq!(offers[0].expires, cw_utils::Expiration::AtHeight(env.block.height));
+
+    // but it no longer reserves its id, the minter offers it again
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter, offer_msg("nft_id_3", None))
+        .unwrap();
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let offers: Vec<Offer> = from_json(&query_res).unwrap();
+    assert_eq!(offers.len(), 1);
+    assert_eq!(offers[0].expires, cw_utils::Expiration::Never {});
+
+    // the minter offers a path relative to a base uri, as long as the maximum length allows
+    let admin = mock_info(MINTER, &[]);
+    let update_msg = crate::msg::ExecuteMsg::UpdateUriConfig {
+        config: UriConfig {
+            base_uri: Some("ipfs://QmBase/".to_string()),
+            ..UriConfig::default()
+        },
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), admin.clone(), update_msg)
+        .unwrap();
+    let path = "a".repeat(505);
+    let mut relative_offer = offer_msg("nft_id_5", None);
+    if let crate::msg::ExecuteMsg::Offer(msg) = &mut relative_offer {
+        msg.nft_uri = Some(path.clone());
+    }
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), admin.clone(), relative_offer)
+        .unwrap();
+
+    // the offer is still accepted once the uri rules no longer allow its metadata
+    let update_msg = crate::msg::ExecuteMsg::UpdateUriConfig {
+        config: UriConfig {
+            allowed_schemes: vec!["https".to_string()],
+            ..UriConfig::default()
+        },
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), admin, update_msg)
+        .unwrap();
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), crate::msg::ExecuteMsg::Accept { nft_id: "nft_id_5".to_string() })
+        .unwrap();
+    let query_res = contract.query(deps.as_ref(), env, crate::msg::QueryMsg::NftInfo { nft_id: "nft_id_5".to_string() }).unwrap();
+    let nft_info: NftInfo = from_json(&query_res).unwrap();
+    assert_eq!(nft_info.nft_uri, format!("ipfs://QmBase/{}", path));
+}
+
+// function to test users claim nfts of a campaign
+#[test]
+fn test_claim() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+
+    // get contract by using default Aura4973 contract
+    let contract = Aura4973::default();
+
+    // prepare the env
+    let mut env = mock_env();
+    let minter = mock_info(MINTER, &[]);
+
+    // build a merkle tree over two claims, the fields of a leaf are length prefixed
+    let leaf_1: [u8; 32] = Sha256::digest(b"\x00\x00\x00\x0balice_claim\x00\x00\x00\x05alice\x00\x00\x00\x0cipfs://alice").into();
+    let leaf_2: [u8; 32] = Sha256::digest(b"\x00\x00\x00\x09bob_claim\x00\x00\x00\x03bob\x00\x00\x00\x0aipfs://bob").into();
+
+    // the secret code is a private key, its public key is registered with the campaign
+    let code = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+    let code_key = HexBinary::from(code.verifying_key().to_encoded_point(true).as_bytes());
+    let mut pair = [leaf_1, leaf_2];
+    pair.sort_unstable();
+    let root = HexBinary::from(Sha256::digest(pair.concat()).to_vec());
+
+    // the minter creates a campaign starting in one hour with a merkle root and a secret code
+    let create_msg = crate::msg::ExecuteMsg::CreateCampaign(crate::msg::CampaignMsg {
+        id: "event".to_string(),
+        nft_uri: Some("ipfs://event".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+        merkle_root: Some(root),
+        code_keys: vec![code_key.clone()],
+        max_claims: Some(2),
+        start: Some(env.block.time.plus_seconds(3600)),
+        end: None,
+    });
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), create_msg.clone())
+        .unwrap();
+
+    // the metadata and the period are checked when creating the campaign
+    let mut invalid_msg = create_msg.clone();
+    if let crate::msg::ExecuteMsg::CreateCampaign(msg) = &mut invalid_msg {
+        msg.id = "invalid".to_string();
+        msg.nft_uri = Some("ftp://event".to_string());
+    }
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), invalid_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::UriSchemeNotAllowed { scheme: "ftp".to_string() });
+
+    let mut invalid_msg = create_msg.clone();
+    if let crate::msg::ExecuteMsg::CreateCampaign(msg) = &mut invalid_msg {
+        msg.id = "invalid".to_string();
+        msg.nft_uri = None;
+    }
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), invalid_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::MissingNftUri {});
+
+    let mut invalid_msg = create_msg.clone();
+    if let crate::msg::ExecuteMsg::CreateCampaign(msg) = &mut invalid_msg {
+        msg.id = "invalid".to_string();
+        msg.end = msg.start;
+    }
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), invalid_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::InvalidCampaignPeriod {});
+
+    // the ids of claimed nfts cannot be minted directly
+    let mint_msg = |nft_id: &str| crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: nft_id.to_string(),
+        owner: "alice".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("event/1"))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NftIdReserved { nft_id: "event/1".to_string(), campaign_id: "event".to_string() });
+
+    // nor can a campaign be created over ids already in use
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("party/1"))
+        .unwrap();
+    let mut party_msg = create_msg;
+    if let crate::msg::ExecuteMsg::CreateCampaign(msg) = &mut party_msg {
+        msg.id = "party".to_string();
+    }
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter, party_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::CampaignIdsInUse { campaign_id: "party".to_string() });
+
+    // prepare the claim of alice
+    let alice = mock_info("alice", &[]);
+    let alice_claim = crate::msg::ExecuteMsg::Claim {
+        campaign_id: "event".to_string(),
+        proof: crate::msg::ClaimProof::Merkle {
+            claim_id: "alice_claim".to_string(),
+            nft_uri: "ipfs://alice".to_string(),
+            proof: vec![HexBinary::from(leaf_2)],
+        },
+    };
+
+    // the campaign has not started yet
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), alice.clone(), alice_claim.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::CampaignNotStarted {});
+    env.block.time = env.block.time.plus_seconds(3600);
+
+    // moving bytes from the uri to the claim id does not keep the leaf
+    let shifted_claim = crate::msg::ExecuteMsg::Claim {
+        campaign_id: "event".to_string(),
+        proof: crate::msg::ClaimProof::Merkle {
+            claim_id: "alice_claimipfs".to_string(),
+            nft_uri: "://alice".to_string(),
+            proof: vec![HexBinary::from(leaf_2)],
+        },
+    };
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), alice.clone(), shifted_claim)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::InvalidProof {});
+
+    // a proof for other metadata is invalid
+    let forged_claim = crate::msg::ExecuteMsg::Claim {
+        campaign_id: "event".to_string(),
+        proof: crate::msg::ClaimProof::Merkle {
+            claim_id: "alice_claim".to_string(),
+            nft_uri: "ipfs://forged".to_string(),
+            proof: vec![HexBinary::from(leaf_2)],
+        },
+    };
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), alice.clone(), forged_claim)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::InvalidProof {});
+
+    // the proof of alice cannot be used by anyone else
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), alice_claim.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::InvalidProof {});
+
+    // alice claims with her merkle proof, but only once
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), alice.clone(), alice_claim.clone())
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), alice, alice_claim)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::AlreadyClaimed {});
+
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::NftInfo { nft_id: "event/1".to_string() }).unwrap();
+    let nft_info: NftInfo = from_json(&query_res).unwrap();
+    assert_eq!(nft_info.owner, "alice".to_string());
+    assert_eq!(nft_info.nft_uri, "ipfs://alice".to_string());
+
+    let query_msg = crate::msg::QueryMsg::IsClaimed {
+        campaign_id: "event".to_string(),
+        claim_key: "alice_claim".to_string(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let res: IsClaimedResponse = from_json(&query_res).unwrap();
+    assert!(res.is_claimed);
+
+    // carol claims by signing her address with the secret code
+    let message = Sha256::digest(b"\x00\x00\x00\x0fcosmos2contract\x00\x00\x00\x05event\x00\x00\x00\x05carol");
+    let signature: Signature = code.sign_prehash(&message).unwrap();
+    let secret_claim = crate::msg::ExecuteMsg::Claim {
+        campaign_id: "event".to_string(),
+        proof: crate::msg::ClaimProof::Secret {
+            public_key: code_key.clone(),
+            signature: HexBinary::from(&signature.to_bytes()[..]),
+        },
+    };
+
+    // the signature of carol cannot be used by anyone else
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), secret_claim.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::InvalidProof {});
+
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), secret_claim)
+        .unwrap();
+
+    let query_msg = crate::msg::QueryMsg::IsClaimed {
+        campaign_id: "event".to_string(),
+        claim_key: code_key.to_hex(),
+    };
+    let query_res = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let res: IsClaimedResponse = from_json(&query_res).unwrap();
+    assert!(res.is_claimed);
+
+    let query_res = contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::NftInfo { nft_id: "event/2".to_string() }).unwrap();
+    let nft_info: NftInfo = from_json(&query_res).unwrap();
+    assert_eq!(nft_info.owner, "carol".to_string());
+    assert_eq!(nft_info.nft_uri, "ipfs://event".to_string());
+
+    // the campaign is capped at two claims
+    let bob_claim = crate::msg::ExecuteMsg::Claim {
+        campaign_id: "event".to_string(),
+        proof: crate::msg::ClaimProof::Merkle {
+            claim_id: "bob_claim".to_string(),
+            nft_uri: "ipfs://bob".to_string(),
+            proof: vec![HexBinary::from(leaf_1)],
+        },
+    };
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), bob_claim)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::CampaignCapReached {});
+
+    let query_res = contract.query(deps.as_ref(), env, crate::msg::QueryMsg::Campaign { campaign_id: "event".to_string() }).unwrap();
+    let campaign: Campaign = from_json(&query_res).unwrap();
+    assert_eq!(campaign.claimed, 2);
+
+}
+
+// function to test mint fees are collected, overpayment refunded and fees withdrawn
+#[test]
+fn test_mint_fee() {
+    // prepare the app with funds for the minter and the owner
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(MINTER), coins(1000, "uaura"))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked("owner"), coins(500, "uaura"))
+            .unwrap();
+    });
+
+    // store and instantiate the contract with a fee of 100uaura, a zero fee is rejected
+    let code_id = app.store_code(aura4973_contract());
+    let mut instantiate_msg = crate::msg::InstantiateMsg {
+        name: "Aura 4973".to_string(),
+        symbol: "A4973".to_string(),
+        minter: String::from(MINTER),
+        admin: None,
+        uri_config: None,
+        recovery_config: None,
+        mint_fee: Some(coin(0, "uaura")),
+        treasury: Some("treasury".to_string()),
+    };
+    let err = app
+        .instantiate_contract(code_id, Addr::unchecked(MINTER), &instantiate_msg, &[], "aura4973", None)
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<crate::error::ContractError>().unwrap(),
+        crate::error::ContractError::ZeroMintFee {}
+    );
+    instantiate_msg.mint_fee = Some(coin(100, "uaura"));
+    let contract_addr = app
+        .instantiate_contract(code_id, Addr::unchecked(MINTER), &instantiate_msg, &[], "aura4973", None)
+        .unwrap();
+
+    // the fee cannot be updated to zero either
+    let update_msg = crate::msg::ExecuteMsg::UpdateMintFee {
+        fee: Some(coin(0, "uaura")),
+        treasury: "treasury".to_string(),
+    };
+    let err = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &update_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<crate::error::ContractError>().unwrap(),
+        crate::error::ContractError::ZeroMintFee {}
+    );
+
+    // helper to build a minting message
+    let mint_msg = |nft_id: &str| crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: nft_id.to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+
+    // minting without enough funds fails
+    let err = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &mint_msg("nft_id_1"), &coins(50, "uaura"))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<crate::error::ContractError>().unwrap(),
+        crate::error::ContractError::InsufficientFee { required: coin(100, "uaura") }
+    );
+
+    // the minter overpays and gets the excess back
+    let _res = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &mint_msg("nft_id_1"), &coins(150, "uaura"))
+        .unwrap();
+    let balance = app.wrap().query_balance(MINTER, "uaura").unwrap();
+    assert_eq!(balance.amount.u128(), 900);
+    let balance = app.wrap().query_balance(&contract_addr, "uaura").unwrap();
+    assert_eq!(balance.amount.u128(), 100);
+
+    // a random user cannot withdraw the fees
+    let withdraw_msg = crate::msg::ExecuteMsg::Withdraw {
+        amount: coin(100, "uaura"),
+        to: None,
+    };
+    let err = app
+        .execute_contract(Addr::unchecked("random"), contract_addr.clone(), &withdraw_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<crate::error::ContractError>().unwrap(),
+        crate::error::ContractError::Unauthorized {}
+    );
+
+    // offering is free, the recipient pays the fee when accepting
+    let offer_msg = crate::msg::ExecuteMsg::Offer(crate::msg::OfferMsg {
+        nft_id: "nft_id_2".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+        expires: None,
+    });
+    let _res = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &offer_msg, &[])
+        .unwrap();
+    let accept_msg = crate::msg::ExecuteMsg::Accept {
+        nft_id: "nft_id_2".to_string(),
+    };
+    let err = app
+        .execute_contract(Addr::unchecked("owner"), contract_addr.clone(), &accept_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<crate::error::ContractError>().unwrap(),
+        crate::error::ContractError::InsufficientFee { required: coin(100, "uaura") }
+    );
+    let _res = app
+        .execute_contract(Addr::unchecked("owner"), contract_addr.clone(), &accept_msg, &coins(100, "uaura"))
+        .unwrap();
+    let balance = app.wrap().query_balance("owner", "uaura").unwrap();
+    assert_eq!(balance.amount.u128(), 400);
+    let balance = app.wrap().query_balance(&contract_addr, "uaura").unwrap();
+    assert_eq!(balance.amount.u128(), 200);
+
+    // the admin withdraws the fees to the treasury
+    let withdraw_msg = crate::msg::ExecuteMsg::Withdraw {
+        amount: coin(200, "uaura"),
+        to: None,
+    };
+    let _res = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &withdraw_msg, &[])
+        .unwrap();
+    let balance = app.wrap().query_balance("treasury", "uaura").unwrap();
+    assert_eq!(balance.amount.u128(), 200);
+    let balance = app.wrap().query_balance(&contract_addr, "uaura").unwrap();
+    assert_eq!(balance.amount.u128(), 0);
+
+}
+
+// hook messages received by the mock hook contract
+const RECEIVED_HOOKS: Item<Vec<AbtHookMsg>> = Item::new("received_hooks");
+
+// function to wrap a mock contract recording the hook messages it receives
+fn hook_receiver_contract() -> Box<dyn Contract<Empty>> {
+    fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: AbtHookExecuteMsg) -> StdResult<Response> {
+        let AbtHookExecuteMsg::AbtHook(hook_msg) = msg;
+        let mut received = RECEIVED_HOOKS.may_load(deps.storage)?.unwrap_or_default();
+        received.push(hook_msg);
+        RECEIVED_HOOKS.save(deps.storage, &received)?;
+        Ok(Response::new())
+    }
+    fn instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+    fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        to_json_binary(&RECEIVED_HOOKS.may_load(deps.storage)?.unwrap_or_default())
+    }
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+// function for testing hook notifications
+#[test]
+fn test_hooks() {
+    let mut app = App::default();
+
+    // store and instantiate the contract and the hook receiver
+    let code_id = app.store_code(aura4973_contract());
+    let instantiate_msg = crate::msg::InstantiateMsg {
+        name: "Aura 4973".to_string(),
+        symbol: "A4973".to_string(),
+        minter: String::from(MINTER),
+        admin: None,
+        uri_config: None,
+        recovery_config: None,
+        mint_fee: None,
+        treasury: None,
+    };
+    let contract_addr = app
+        .instantiate_contract(code_id, Addr::unchecked(MINTER), &instantiate_msg, &[], "aura4973", None)
+        .unwrap();
+    let receiver_code_id = app.store_code(hook_receiver_contract());
+    let receiver_addr = app
+        .instantiate_contract(receiver_code_id, Addr::unchecked(MINTER), &Empty {}, &[], "receiver", None)
+        .unwrap();
+
+    // a random user cannot register a hook
+    let add_hook_msg = crate::msg::ExecuteMsg::AddHook { addr: receiver_addr.to_string() };
+    let err = app
+        .execute_contract(Addr::unchecked("random"), contract_addr.clone(), &add_hook_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<crate::error::ContractError>().unwrap(),
+        crate::error::ContractError::Unauthorized {}
+    );
+
+    // the admin registers the hook, but only once
+    let _res = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &add_hook_msg, &[])
+        .unwrap();
+    let err = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &add_hook_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<crate::error::ContractError>().unwrap(),
+        crate::error::ContractError::Hook(cw_controllers::HookError::HookAlreadyRegistered {})
+    );
+    let hooks: HooksResponse = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &crate::msg::QueryMsg::Hooks {})
+        .unwrap();
+    assert_eq!(hooks.hooks, vec![receiver_addr.to_string()]);
+
+    // mint, unequip, equip and unadmit a nft
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id_1".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+    let _res = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &mint_msg, &[])
+        .unwrap();
+    let _res = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            contract_addr.clone(),
+            &crate::msg::ExecuteMsg::UnEquip { nft_id: "nft_id_1".to_string() },
+            &[],
+        )
+        .unwrap();
+    let _res = app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            contract_addr.clone(),
+            &crate::msg::ExecuteMsg::Equip { nft_id: "nft_id_1".to_string() },
+            &[],
+        )
+        .unwrap();
+    let _res = app
+        .execute_contract(
+            Addr::unchecked(MINTER),
+            contract_addr.clone(),
+            &crate::msg::ExecuteMsg::UnAdmit { nft_id: "nft_id_1".to_string() },
+            &[],
+        )
+        .unwrap();
+
+    // the minter recovers the nfts of owner right away
+    let config_msg = crate::msg::ExecuteMsg::UpdateRecoveryConfig {
+        config: RecoveryConfig { delay: 0, require_confirmation: false, guardian_delay: 0 },
+    };
+    let _res = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &config_msg, &[])
+        .unwrap();
+    let recover_msg = crate::msg::ExecuteMsg::Recover { from: "owner".to_string(), to: "new_owner".to_string() };
+    let _res = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &recover_msg, &[])
+        .unwrap();
+
+    // the receiver got notified of every change
+    let received: Vec<AbtHookMsg> = app.wrap().query_wasm_smart(&receiver_addr, &Empty {}).unwrap();
+    let events: Vec<AbtEvent> = received.iter().map(|msg| msg.event.clone()).collect();
+    assert_eq!(events, vec![AbtEvent::Mint, AbtEvent::Unequip, AbtEvent::Equip, AbtEvent::Revoke, AbtEvent::Rebind]);
+    assert!(received[..4].iter().all(|msg| msg.nft_id == "nft_id_1" && msg.owner == "owner" && msg.previous_owner.is_none()));
+    assert_eq!(received[4], AbtHookMsg::new(AbtEvent::Rebind, "nft_id_1", "new_owner").with_previous_owner("owner"));
+
+    // once removed, the hook is not notified anymore
+    let _res = app
+        .execute_contract(
+            Addr::unchecked(MINTER),
+            contract_addr.clone(),
+            &crate::msg::ExecuteMsg::RemoveHook { addr: receiver_addr.to_string() },
+            &[],
+        )
+        .unwrap();
+    let _res = app
+        .execute_contract(
+            Addr::unchecked("new_owner"),
+            contract_addr.clone(),
+            &crate::msg::ExecuteMsg::UnEquip { nft_id: "nft_id_1".to_string() },
+            &[],
+        )
+        .unwrap();
+    let received: Vec<AbtHookMsg> = app.wrap().query_wasm_smart(&receiver_addr, &Empty {}).unwrap();
+    assert_eq!(received.len(), 5);
+
+}
+
+// nfts received by the mock receiver contract
+const RECEIVED_NFTS: Item<Vec<AbtReceiveMsg>> = Item::new("received_nfts");
+
+// execute message of the mock receiver contract
+#[cosmwasm_schema::cw_serde]
+enum ReceiverExecuteMsg {
+    ReceiveAbt(AbtReceiveMsg),
+}
+
+// function to wrap a mock contract recording the nfts it receives and refusing the "reject" message
+fn nft_receiver_contract() -> Box<dyn Contract<Empty>> {
+    fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ReceiverExecuteMsg) -> StdResult<Response> {
+        let ReceiverExecuteMsg::ReceiveAbt(receive_msg) = msg;
+        if receive_msg.msg.as_slice() == b"reject" {
+            return Err(StdError::generic_err("rejected"));
+        }
+        let mut received = RECEIVED_NFTS.may_load(deps.storage)?.unwrap_or_default();
+        received.push(receive_msg);
+        RECEIVED_NFTS.save(deps.storage, &received)?;
+        Ok(Response::new())
+    }
+    fn instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+    fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        to_json_binary(&RECEIVED_NFTS.may_load(deps.storage)?.unwrap_or_default())
+    }
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+// function for testing minting a nft to a contract that is notified of it
+#[test]
+fn test_mint_and_notify() {
+    let mut app = App::default();
+
+    // store and instantiate the contract and the receiver
+    let code_id = app.store_code(aura4973_contract());
+    let instantiate_msg = crate::msg::InstantiateMsg {
+        name: "Aura 4973".to_string(),
+        symbol: "A4973".to_string(),
+        minter: String::from(MINTER),
+        admin: None,
+        uri_config: None,
+        recovery_config: None,
+        mint_fee: None,
+        treasury: None,
+    };
+    let contract_addr = app
+        .instantiate_contract(code_id, Addr::unchecked(MINTER), &instantiate_msg, &[], "aura4973", None)
+        .unwrap();
+    let receiver_code_id = app.store_code(nft_receiver_contract());
+    let receiver_addr = app
+        .instantiate_contract(receiver_code_id, Addr::unchecked(MINTER), &Empty {}, &[], "receiver", None)
+        .unwrap();
+
+    // helper to build a minting message
+    let mint_msg = |nft_id: &str, msg: &[u8]| crate::msg::ExecuteMsg::MintAndNotify(crate::msg::MintAndNotifyMsg {
+        nft_id: nft_id.to_string(),
+        owner: receiver_addr.to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+        msg: Binary::from(msg),
+    });
+
+    // the receiver is notified of the minted nft
+    let _res = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &mint_msg("nft_id_1", b"grant_role"), &[])
+        .unwrap();
+    let received: Vec<AbtReceiveMsg> = app.wrap().query_wasm_smart(&receiver_addr, &Empty {}).unwrap();
+    assert_eq!(
+        received,
+        vec![AbtReceiveMsg {
+            sender: MINTER.to_string(),
+            nft_id: "nft_id_1".to_string(),
+            msg: Binary::from(b"grant_role"),
+        }]
+    );
+    let owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &crate::msg::QueryMsg::OwnerOf { nft_id: "nft_id_1".to_string() })
+        .unwrap();
+    assert_eq!(owner.owner, receiver_addr.to_string());
+
+    // the nft is not minted if the receiver fails
+    let _err = app
+        .execute_contract(Addr::unchecked(MINTER), contract_addr.clone(), &mint_msg("nft_id_2", b"reject"), &[])
+        .unwrap_err();
+    let num_nfts: NumNftsResponse = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &crate::msg::QueryMsg::NumNfts {})
+        .unwrap();
+    assert_eq!(num_nfts.count, 1);
+
+}
+
+// function for testing the events emitted on every state transition
+#[test]
+fn test_events() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+    let env = mock_env();
+    let block_height = env.block.height.to_string();
+    let block_time = env.block.time.seconds().to_string();
+
+    // helper to build the expected attributes of an event
+    let attributes = |sender: &str, state_before: &str, state_after: &str| vec![
+        attr("nft_id", "nft_id"),
+        attr("owner", "owner"),
+        attr("sender", sender),
+        attr("state_before", state_before),
+        attr("state_after", state_after),
+        attr("block_height", &block_height),
+        attr("block_time", &block_time),
+        attr("issuer", MINTER),
+    ];
+
+    // minting emits abt_mint
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(res.events[0].ty, "abt_mint");
+    assert_eq!(res.events[0].attributes, attributes(MINTER, "none", "equipped"));
+
+    // unequipping and equipping emit abt_unequip and abt_equip
+    let unequip_msg = crate::msg::ExecuteMsg::UnEquip { nft_id: "nft_id".to_string() };
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), unequip_msg)
+        .unwrap();
+    assert_eq!(res.events[0].ty, "abt_unequip");
+    assert_eq!(res.events[0].attributes, attributes("owner", "equipped", "unequipped"));
+
+    let equip_msg = crate::msg::ExecuteMsg::Equip { nft_id: "nft_id".to_string() };
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), equip_msg)
+        .unwrap();
+    assert_eq!(res.events[0].ty, "abt_equip");
+    assert_eq!(res.events[0].attributes, attributes("owner", "unequipped", "equipped"));
+
+    // unadmitting emits abt_revoke
+    let unadmit_msg = crate::msg::ExecuteMsg::UnAdmit { nft_id: "nft_id".to_string() };
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), unadmit_msg)
+        .unwrap();
+    assert_eq!(res.events[0].ty, "abt_revoke");
+    assert_eq!(res.events[0].attributes, attributes(MINTER, "equipped", "revoked"));
+
+    // recovering emits abt_rebind with the previous owner
+    let recover_msg = crate::msg::ExecuteMsg::UpdateRecoveryConfig {
+        config: RecoveryConfig { delay: 0, require_confirmation: false, guardian_delay: 0 },
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), recover_msg)
+        .unwrap();
+    let recover_msg = crate::msg::ExecuteMsg::Recover { from: "owner".to_string(), to: "new_owner".to_string() };
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), recover_msg)
+        .unwrap();
+    assert_eq!(res.events[0].ty, "abt_rebind");
+    assert_eq!(res.events[0].attributes[1], attr("owner", "new_owner"));
+    assert_eq!(res.events[0].attributes[8], attr("previous_owner", "owner"));
+
+}
+
+// function for testing the migration of the storage of an older version of the contract
+#[test]
+fn test_migrate() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+
+    // older versions had neither the settings nor the (owner, status) index, and stored
+    // nfts with only their first fields. Only the first of 32 nfts is equipped
+    contract.admin.remove(deps.as_mut().storage);
+    contract.uri_config.remove(deps.as_mut().storage);
+    let old_nfts: Map<&str, Empty> = Map::new("tokens");
+    for i in 1..=32 {
+        let nft_id = format!("nft_id_{:02}", i);
+        let nft_info = format!(
+            r#"{{"id":"{}","owner":"owner","nft_uri":"ipfs://nft_uri","equiped":{},"is_admitted":false}}"#,
+            nft_id,
+            i == 1
+        );
+        deps.as_mut().storage.set(&old_nfts.key(&nft_id), nft_info.as_bytes());
+    }
+    let indexed = contract.nfts.idx.owner_status
+        .prefix(("owner".to_string(), "equipped".to_string()))
+        .keys(deps.as_ref().storage, None, None, Order::Ascending)
+        .count();
+    assert_eq!(indexed, 0);
+
+    // the migration fills the settings and reindexes the first page of nfts
+    let res = crate::contract::migrate(deps.as_mut(), mock_env(), crate::msg::MigrateMsg { admin: None }).unwrap();
+    assert!(res.attributes.contains(&attr("reindexed", "30")));
+    assert!(res.attributes.contains(&attr("next", "nft_id_30")));
+    assert_eq!(contract.admin.load(deps.as_ref().storage).unwrap(), MINTER);
+    assert_eq!(contract.uri_config.load(deps.as_ref().storage).unwrap(), UriConfig::default());
+
+    // nothing else can be executed until the reindex is finished
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), crate::msg::ExecuteMsg::Equip { nft_id: "nft_id_02".to_string() })
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::ReindexPending {});
+
+    // only the admin reindexes the remaining nfts
+    let reindex_msg = crate::msg::ExecuteMsg::Reindex { limit: None };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("random", &[]), reindex_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), reindex_msg.clone())
+        .unwrap();
+    assert!(res.attributes.contains(&attr("reindexed", "2")));
+    assert!(!res.attributes.iter().any(|attr| attr.key == "next"));
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), reindex_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NoReindex {});
+
+    let equipped = contract.all_equipped_nft_of(deps.as_ref(), "owner".to_string()).unwrap();
+    assert_eq!(equipped.len(), 1);
+    assert_eq!(equipped[0].id, "nft_id_01");
+    assert_eq!(equipped[0].issuer, MINTER);
+    assert_eq!(equipped[0].class_id, None);
+    let balance = contract.balances.load(deps.as_ref().storage, "owner").unwrap();
+    assert_eq!((balance.equipped, balance.unequipped), (1, 31));
+    assert_eq!(contract.num_holders.load(deps.as_ref().storage).unwrap(), 1);
+
+    // migrating again does not count the nfts twice, and may name another admin
+    let res = crate::contract::migrate(deps.as_mut(), mock_env(), crate::msg::MigrateMsg { admin: Some("new_admin".to_string()) }).unwrap();
+    assert!(res.attributes.contains(&attr("reindexed", "0")));
+    assert_eq!(contract.admin.load(deps.as_ref().storage).unwrap(), "new_admin");
+    let balance = contract.balances.load(deps.as_ref().storage, "owner").unwrap();
+    assert_eq!((balance.equipped, balance.unequipped), (1, 31));
+
+    // another contract cannot be migrated to this one
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.15.0").unwrap();
+    let err = crate::contract::migrate(deps.as_mut(), mock_env(), crate::msg::MigrateMsg { admin: None }).unwrap_err();
+    assert_eq!(
+        err,
+        crate::error::ContractError::CannotMigrate { previous_contract: "crates.io:cw721-base".to_string() }
+    );
+
+}
+
+// function for testing the counters of nfts per status and per owner
+#[test]
+fn test_stats() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+    let env = mock_env();
+
+    // mint two nfts to owner and one to another owner
+    for (nft_id, owner) in [("nft_id_1", "owner"), ("nft_id_2", "owner"), ("nft_id_3", "other")] {
+        let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+            nft_id: nft_id.to_string(),
+            owner: owner.to_string(),
+            nft_uri: Some("ipfs://nft_uri".to_string()),
+            metadata_hash: None,
+            class_id: None,
+            kind: None,
+        });
+        let _res = contract
+            .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), mint_msg)
+            .unwrap();
+    }
+
+    // owner unequips a nft and the minter revokes the other one
+    let unequip_msg = crate::msg::ExecuteMsg::UnEquip { nft_id: "nft_id_1".to_string() };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), unequip_msg)
+        .unwrap();
+    let unadmit_msg = crate::msg::ExecuteMsg::UnAdmit { nft_id: "nft_id_2".to_string() };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), unadmit_msg)
+        .unwrap();
+
+    // check the counters
+    let stats: StatsResponse = from_json(
+        contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::Stats {}).unwrap()
+    ).unwrap();
+    assert_eq!(stats, StatsResponse { issued: 3, equipped: 1, unequipped: 1, revoked: 1, burned: 0 });
+
+    let query_balance = |deps: cosmwasm_std::Deps, owner: &str| -> BalanceResponse {
+        from_json(
+            contract.query(deps, mock_env(), crate::msg::QueryMsg::BalanceOf { owner: owner.to_string() }).unwrap()
+        ).unwrap()
+    };
+    assert_eq!(query_balance(deps.as_ref(), "owner"), BalanceResponse { balance: 2, equipped: 0, unequipped: 1, revoked: 1 });
+    assert_eq!(query_balance(deps.as_ref(), "other"), BalanceResponse { balance: 1, equipped: 1, unequipped: 0, revoked: 0 });
+
+    // recovering moves the balance to the new address
+    let config_msg = crate::msg::ExecuteMsg::UpdateRecoveryConfig {
+        config: RecoveryConfig { delay: 0, require_confirmation: false, guardian_delay: 0 },
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), config_msg)
+        .unwrap();
+    let recover_msg = crate::msg::ExecuteMsg::Recover { from: "owner".to_string(), to: "new_owner".to_string() };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), recover_msg)
+        .unwrap();
+    assert_eq!(query_balance(deps.as_ref(), "owner"), BalanceResponse { balance: 0, equipped: 0, unequipped: 0, revoked: 0 });
+    assert_eq!(query_balance(deps.as_ref(), "new_owner"), BalanceResponse { balance: 2, equipped: 0, unequipped: 1, revoked: 1 });
+
+    // uncounting a nft that was never counted fails instead of wrapping around
+    let uncounted = NftInfo {
+        id: "nft_id_4".to_string(),
+        owner: "stranger".to_string(),
+        nft_uri: "ipfs://nft_uri".to_string(),
+        equiped: true,
+        is_admitted: false,
+        metadata_hash: None,
+        class_id: None,
+        expires_at: None,
+        kind: None,
+        issuer: MINTER.to_string(),
+        issued_at_height: 0,
+        revoke_reason: None,
+        base_uri_version: None,
+    };
+    let err = contract
+        .update_counts(deps.as_mut().storage, &uncounted, false, env.block.height)
+        .unwrap_err();
+    assert!(matches!(err, StdError::Overflow { .. }));
+
+}
+
+// function for testing the enumeration of holders
+#[test]
+fn test_holders() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+    let env = mock_env();
+
+    // mint nfts to three owners, one of them getting two nfts
+    for (nft_id, owner) in [("nft_id_1", "owner_a"), ("nft_id_2", "owner_b"), ("nft_id_3", "owner_b"), ("nft_id_4", "owner_c")] {
+        let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+            nft_id: nft_id.to_string(),
+            owner: owner.to_string(),
+            nft_uri: Some("ipfs://nft_uri".to_string()),
+            metadata_hash: None,
+            class_id: None,
+            kind: None,
+        });
+        let _res = contract
+            .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), mint_msg)
+            .unwrap();
+    }
+
+    // helpers to query the holders
+    let query_holders = |deps: cosmwasm_std::Deps, start_after: Option<&str>, limit: Option<u32>| -> Vec<String> {
+        let query_msg = crate::msg::QueryMsg::Holders { start_after: start_after.map(String::from), limit };
+        from_json(contract.query(deps, mock_env(), query_msg).unwrap()).unwrap()
+    };
+    let query_num_holders = |deps: cosmwasm_std::Deps| -> u64 {
+        let res: NumHoldersResponse = from_json(
+            contract.query(deps, mock_env(), crate::msg::QueryMsg::NumHolders {}).unwrap()
+        ).unwrap();
+        res.count
+    };
+
+    // every holder is listed once, page by page
+    assert_eq!(query_num_holders(deps.as_ref()), 3);
+    assert_eq!(query_holders(deps.as_ref(), None, Some(2)), vec!["owner_a", "owner_b"]);
+    assert_eq!(query_holders(deps.as_ref(), Some("owner_b"), Some(2)), vec!["owner_c"]);
+
+    // recovering to a new address replaces the holder
+    let config_msg = crate::msg::ExecuteMsg::UpdateRecoveryConfig {
+        config: RecoveryConfig { delay: 0, require_confirmation: false, guardian_delay: 0 },
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), config_msg)
+        .unwrap();
+    let recover_msg = crate::msg::ExecuteMsg::Recover { from: "owner_b".to_string(), to: "owner_d".to_string() };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), recover_msg)
+        .unwrap();
+    assert_eq!(query_num_holders(deps.as_ref()), 3);
+    assert_eq!(query_holders(deps.as_ref(), None, None), vec!["owner_a", "owner_c", "owner_d"]);
+
+    // recovering to an existing holder merges them
+    let recover_msg = crate::msg::ExecuteMsg::Recover { from: "owner_c".to_string(), to: "owner_a".to_string() };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), recover_msg)
+        .unwrap();
+    assert_eq!(query_num_holders(deps.as_ref()), 2);
+    assert_eq!(query_holders(deps.as_ref(), None, None), vec!["owner_a", "owner_d"]);
+
+}
+
+// function for testing the query of nfts by issuer
+#[test]
+fn test_tokens_by_issuer() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+    let env = mock_env();
+
+    // the minter creates a badge class that a teacher may issue
+    let create_msg = crate::msg::ExecuteMsg::CreateBadgeClass(crate::msg::BadgeClassMsg {
+        id: "course_x".to_string(),
+        nft_uri: "ipfs://course_x".to_string(),
+        metadata_hash: None,
+        max_supply: None,
+        issuers: vec!["teacher".to_string()],
+        expires_after: None,
+        kind: None,
+    });
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), create_msg)
+        .unwrap();
+
+    // the minter and the teacher both issue nfts
+    for (nft_id, issuer, class_id) in [("nft_id_1", MINTER, None), ("nft_id_2", "teacher", Some("course_x")), ("nft_id_3", "teacher", Some("course_x"))] {
+        let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+            nft_id: nft_id.to_string(),
+            owner: "owner".to_string(),
+            nft_uri: class_id.is_none().then(|| "ipfs://nft_uri".to_string()),
+            metadata_hash: None,
+            class_id: class_id.map(String::from),
+            kind: None,
+        });
+        let _res = contract
+            .execute(deps.as_mut(), env.clone(), mock_info(issuer, &[]), mint_msg)
+            .unwrap();
+    }
+
+    // helper to query the nfts of an issuer
+    let query_tokens = |deps: cosmwasm_std::Deps, issuer: &str, start_after: Option<&str>| -> Vec<String> {
+        let query_msg = crate::msg::QueryMsg::TokensByIssuer {
+            issuer: issuer.to_string(),
+            start_after: start_after.map(String::from),
+            limit: Some(1),
+        };
+        let nfts: Vec<NftInfo> = from_json(contract.query(deps, mock_env(), query_msg).unwrap()).unwrap();
+        nfts.into_iter().map(|nft_info| nft_info.id).collect()
+    };
+
+    // each issuer only gets its own nfts, page by page
+    assert_eq!(query_tokens(deps.as_ref(), MINTER, None), vec!["nft_id_1"]);
+    assert_eq!(query_tokens(deps.as_ref(), MINTER, Some("nft_id_1")), Vec::<String>::new());
+    assert_eq!(query_tokens(deps.as_ref(), "teacher", None), vec!["nft_id_2"]);
+    assert_eq!(query_tokens(deps.as_ref(), "teacher", Some("nft_id_2")), vec!["nft_id_3"]);
+    assert_eq!(query_tokens(deps.as_ref(), "random", None), Vec::<String>::new());
+
+}
+
+// function for testing the bulk revocation of the nfts of an issuer
+#[test]
+fn test_revoke_issued_by() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+
+    // the minter creates a badge class that a teacher may issue
+    let create_msg = crate::msg::ExecuteMsg::CreateBadgeClass(crate::msg::BadgeClassMsg {
+        id: "course_x".to_string(),
+        nft_uri: "ipfs://course_x".to_string(),
+        metadata_hash: None,
+        max_supply: None,
+        issuers: vec!["teacher".to_string()],
+        expires_after: None,
+        kind: None,
+    });
+    let _res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), create_msg)
+        .unwrap();
+
+    // the teacher issues a nft before its key leaks and three after, the minter issues one
+    let mints = [
+        ("nft_id_1", "teacher", 100),
+        ("nft_id_2", "teacher", 200),
+        ("nft_id_3", "teacher", 200),
+        ("nft_id_4", "teacher", 201),
+        ("nft_id_5", MINTER, 200),
+    ];
+    for (nft_id, issuer, height) in mints {
+        let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+            nft_id: nft_id.to_string(),
+            owner: "owner".to_string(),
+            nft_uri: if issuer == MINTER { Some("ipfs://nft_uri".to_string()) } else { None },
+            metadata_hash: None,
+            class_id: if issuer == MINTER { None } else { Some("course_x".to_string()) },
+            kind: None,
+        });
+        let mut env = mock_env();
+        env.block.height = height;
+        let _res = contract
+            .execute(deps.as_mut(), env, mock_info(issuer, &[]), mint_msg)
+            .unwrap();
+    }
+
+    // helper to build the revocation message
+    let revoke_msg = |start_after: Option<String>| crate::msg::ExecuteMsg::RevokeIssuedBy(crate::msg::RevokeIssuedByMsg {
+        issuer: "teacher".to_string(),
+        since_height: 150,
+        start_after,
+        limit: Some(2),
+        reason: "leaked key".to_string(),
+    });
+
+    // only the admin can revoke in bulk
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("random", &[]), revoke_msg(None))
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+
+    // the admin walks the nfts of the teacher page by page
+    let mut pages = vec![];
+    let mut start_after = None;
+    loop {
+        let res = contract
+            .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), revoke_msg(start_after))
+            .unwrap();
+        let data: RevokeIssuedByResponse = from_json(res.data.unwrap()).unwrap();
+        pages.push(data.revoked);
+        start_after = data.next;
+        if start_after.is_none() {
+            break;
+        }
+    }
+    assert_eq!(pages, vec![1, 2, 0]);
+
+    // only the nfts issued by the teacher after the leak are revoked
+    for (nft_id, revoked) in [("nft_id_1", false), ("nft_id_2", true), ("nft_id_3", true), ("nft_id_4", true), ("nft_id_5", false)] {
+        let nft_info = contract.nfts.load(deps.as_ref().storage, nft_id).unwrap();
+        assert_eq!(nft_info.is_admitted, revoked);
+        assert_eq!(nft_info.revoke_reason.is_some(), revoked);
+    }
+    let nft_info = contract.nfts.load(deps.as_ref().storage, "nft_id_2").unwrap();
+    assert_eq!(nft_info.issued_at_height, 200);
+    assert_eq!(nft_info.revoke_reason, Some("leaked key".to_string()));
+
+    // the admin removes the teacher from the class, which stops it from minting
+    let issuers_msg = crate::msg::ExecuteMsg::UpdateBadgeClassIssuers {
+        class_id: "course_x".to_string(),
+        add: vec![],
+        remove: vec!["teacher".to_string()],
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("random", &[]), issuers_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+    let _res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), issuers_msg)
+        .unwrap();
+    let class_mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id_6".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: None,
+        metadata_hash: None,
+        class_id: Some("course_x".to_string()),
+        kind: None,
+    });
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("teacher", &[]), class_mint_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NotMinter {});
+
+    // the admin replaces the minter, only the new one can mint
+    let minter_msg = crate::msg::ExecuteMsg::UpdateMinter {
+        minter: "new_minter".to_string(),
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("random", &[]), minter_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+    let _res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), minter_msg)
+        .unwrap();
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id_6".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NotMinter {});
+    let _res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("new_minter", &[]), mint_msg)
+        .unwrap();
+
+    // only the admin proposes a new admin, which must accept before it takes over
+    let accept_msg = crate::msg::ExecuteMsg::AcceptAdmin {};
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("new_admin", &[]), accept_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NoPendingAdmin {});
+    let propose_msg = crate::msg::ExecuteMsg::ProposeAdmin {
+        admin: "new_admin".to_string(),
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("new_minter", &[]), propose_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+    let _res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), propose_msg)
+        .unwrap();
+
+    let query_res = contract.query(deps.as_ref(), mock_env(), crate::msg::QueryMsg::Admin {}).unwrap();
+    let res: AdminResponse = from_json(&query_res).unwrap();
+    assert_eq!(res, AdminResponse { admin: MINTER.to_string(), pending_admin: Some("new_admin".to_string()) });
+
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("random", &[]), accept_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+    let _res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info("new_admin", &[]), accept_msg)
+        .unwrap();
+
+    let query_res = contract.query(deps.as_ref(), mock_env(), crate::msg::QueryMsg::Admin {}).unwrap();
+    let res: AdminResponse = from_json(&query_res).unwrap();
+    assert_eq!(res, AdminResponse { admin: "new_admin".to_string(), pending_admin: None });
+
+    // the former admin no longer manages the contract
+    let minter_msg = crate::msg::ExecuteMsg::UpdateMinter {
+        minter: MINTER.to_string(),
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), minter_msg)
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+
+}
+
+// function for testing the lookup of several nfts at once
+#[test]
+fn test_nft_infos() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+    let env = mock_env();
+
+    // mint two nfts
+    for nft_id in ["nft_id_1", "nft_id_2"] {
+        let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+            nft_id: nft_id.to_string(),
+            owner: "owner".to_string(),
+            nft_uri: Some("ipfs://nft_uri".to_string()),
+            metadata_hash: None,
+            class_id: None,
+            kind: None,
+        });
+        let _res = contract
+            .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), mint_msg)
+            .unwrap();
+    }
+
+    // unknown ids are marked instead of failing the query
+    let query_msg = crate::msg::QueryMsg::NftInfos {
+        nft_ids: vec!["nft_id_2".to_string(), "unknown".to_string(), "nft_id_1".to_string()],
+    };
+    let entries: Vec<NftInfoEntry> = from_json(contract.query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].nft_id, "nft_id_2");
+    assert_eq!(entries[0].info.as_ref().unwrap().id, "nft_id_2");
+    assert_eq!(entries[1].nft_id, "unknown");
+    assert_eq!(entries[1].info, None);
+    assert_eq!(entries[2].info.as_ref().unwrap().owner, "owner");
+
+    // the number of ids is limited
+    let query_msg = crate::msg::QueryMsg::NftInfos {
+        nft_ids: (0..31).map(|i| format!("nft_id_{}", i)).collect(),
+    };
+    let _err = contract.query(deps.as_ref(), env, query_msg).unwrap_err();
+
+}
+
+// function for testing the requirements over the nfts of an owner
+#[test]
+fn test_holds() {
+    use crate::msg::Requirement;
+
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+    let env = mock_env();
+
+    // a badge class whose nfts expire after an hour
+    let create_msg = crate::msg::ExecuteMsg::CreateBadgeClass(crate::msg::BadgeClassMsg {
+        id: "pass".to_string(),
+        nft_uri: "ipfs://pass".to_string(),
+        metadata_hash: None,
+        max_supply: None,
+        issuers: vec![],
+        expires_after: Some(3600),
+        kind: None,
+    });
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), create_msg)
+        .unwrap();
+
+    // mint a member, a kyc, an unequipped, a revoked and an expiring nft
+    let mints = [
+        ("member", Some("member"), None),
+        ("kyc", Some("kyc"), None),
+        ("unequipped", Some("speaker"), None),
+        ("revoked", Some("staff"), None),
+        ("pass", None, Some("pass")),
+    ];
+    for (nft_id, kind, class_id) in mints {
+        let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+            nft_id: nft_id.to_string(),
+            owner: "owner".to_string(),
+            nft_uri: class_id.is_none().then(|| "ipfs://nft_uri".to_string()),
+            metadata_hash: None,
+            class_id: class_id.map(String::from),
+            kind: kind.map(String::from),
+        });
+        let _res = contract
+            .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), mint_msg)
+            .unwrap();
+    }
+    let unequip_msg = crate::msg::ExecuteMsg::UnEquip { nft_id: "unequipped".to_string() };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), unequip_msg)
+        .unwrap();
+    let unadmit_msg = crate::msg::ExecuteMsg::UnAdmit { nft_id: "revoked".to_string() };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), unadmit_msg)
+        .unwrap();
+
+    // helpers to build requirements and evaluate them
+    let token = |nft_id: &str| Requirement::Token { nft_id: nft_id.to_string() };
+    let kind = |kind: &str| Requirement::Kind { kind: kind.to_string() };
+    let holds = |deps: cosmwasm_std::Deps, env: cosmwasm_std::Env, owner: &str, requirement: Requirement| -> bool {
+        let query_msg = crate::msg::QueryMsg::Holds { owner: owner.to_string(), requirement };
+        let res: HoldsResponse = from_json(contract.query(deps, env, query_msg).unwrap()).unwrap();
+        res.holds
+    };
+
+    // only equipped, admitted and unexpired nfts count
+    assert!(holds(deps.as_ref(), env.clone(), "owner", token("member")));
+    assert!(holds(deps.as_ref(), env.clone(), "owner", kind("kyc")));
+    assert!(holds(deps.as_ref(), env.clone(), "owner", token("pass")));
+    assert!(!holds(deps.as_ref(), env.clone(), "owner", token("unequipped")));
+    assert!(!holds(deps.as_ref(), env.clone(), "owner", kind("staff")));
+    assert!(!holds(deps.as_ref(), env.clone(), "other", token("member")));
+    let mut later = env.clone();
+    later.block.time = later.block.time.plus_seconds(3600);
+    assert!(!holds(deps.as_ref(), later, "owner", token("pass")));
+
+    // all-of, any-of and n-of combine requirements
+    let all_of = Requirement::AllOf { requirements: vec![kind("member"), kind("kyc")] };
+    assert!(holds(deps.as_ref(), env.clone(), "owner", all_of));
+    let all_of = Requirement::AllOf { requirements: vec![kind("member"), kind("staff")] };
+    assert!(!holds(deps.as_ref(), env.clone(), "owner", all_of));
+    let any_of = Requirement::AnyOf { requirements: vec![kind("staff"), token("pass")] };
+    assert!(holds(deps.as_ref(), env.clone(), "owner", any_of));
+    let n_of = Requirement::NOf { n: 2, requirements: vec![kind("staff"), kind("speaker"), kind("kyc"), token("member")] };
+    assert!(holds(deps.as_ref(), env.clone(), "owner", n_of));
+    let n_of = Requirement::NOf { n: 3, requirements: vec![kind("staff"), kind("speaker"), kind("kyc"), token("member")] };
+    assert!(!holds(deps.as_ref(), env.clone(), "owner", n_of));
+    let nested = Requirement::AllOf {
+        requirements: vec![token("member"), Requirement::AnyOf { requirements: vec![kind("staff"), kind("kyc")] }],
+    };
+    assert!(holds(deps.as_ref(), env.clone(), "owner", nested));
+
+    // requirements are limited in size
+    let too_large = Requirement::AnyOf { requirements: (0..30).map(|i| token(&format!("nft_id_{}", i))).collect() };
+    let query_msg = crate::msg::QueryMsg::Holds { owner: "owner".to_string(), requirement: too_large };
+    let _err = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap_err();
+
+    // empty combinations and n-of out of range are rejected, also when nested
+    let malformed = [
+        Requirement::AllOf { requirements: vec![] },
+        Requirement::AnyOf { requirements: vec![] },
+        Requirement::NOf { n: 0, requirements: vec![token("member")] },
+        Requirement::NOf { n: 2, requirements: vec![token("member")] },
+        Requirement::AnyOf { requirements: vec![token("member"), Requirement::AllOf { requirements: vec![] }] },
+    ];
+    for requirement in malformed {
+        let query_msg = crate::msg::QueryMsg::Holds { owner: "owner".to_string(), requirement };
+        let err = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap_err();
+        assert!(matches!(err, StdError::GenericErr { .. }));
+    }
+
+}
+
+// function for testing the queries of nfts and balances at past heights
+#[test]
+fn test_snapshots() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+
+    // helper to get an env at a given height
+    let env_at = |height: u64| {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    };
+
+    // the minter mints a nft at height 100
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+    let _res = contract
+        .execute(deps.as_mut(), env_at(100), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+
+    // the owner unequips it at height 200
+    let unequip_msg = crate::msg::ExecuteMsg::UnEquip { nft_id: "nft_id".to_string() };
+    let _res = contract
+        .execute(deps.as_mut(), env_at(200), mock_info("owner", &[]), unequip_msg)
+        .unwrap();
+
+    // the minter recovers it to a new address at height 300
+    let config_msg = crate::msg::ExecuteMsg::UpdateRecoveryConfig {
+        config: RecoveryConfig { delay: 0, require_confirmation: false, guardian_delay: 0 },
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env_at(300), mock_info(MINTER, &[]), config_msg)
+        .unwrap();
+    let recover_msg = crate::msg::ExecuteMsg::Recover { from: "owner".to_string(), to: "new_owner".to_string() };
+    let _res = contract
+        .execute(deps.as_mut(), env_at(300), mock_info(MINTER, &[]), recover_msg)
+        .unwrap();
+
+    // helpers to query the past
+    let nft_at = |deps: cosmwasm_std::Deps, height: u64| -> Option<NftInfo> {
+        let query_msg = crate::msg::QueryMsg::NftInfoAtHeight { nft_id: "nft_id".to_string(), height };
+        from_json(contract.query(deps, mock_env(), query_msg).unwrap()).unwrap()
+    };
+    let balance_at = |deps: cosmwasm_std::Deps, owner: &str, height: u64| -> BalanceResponse {
+        let query_msg = crate::msg::QueryMsg::BalanceAtHeight { owner: owner.to_string(), height };
+        from_json(contract.query(deps, mock_env(), query_msg).unwrap()).unwrap()
+    };
+
+    // heights are read at the start of their block
+    assert_eq!(nft_at(deps.as_ref(), 100), None);
+    let nft_info = nft_at(deps.as_ref(), 101).unwrap();
+    assert_eq!((nft_info.owner.as_str(), nft_info.equiped), ("owner", true));
+    let nft_info = nft_at(deps.as_ref(), 250).unwrap();
+    assert_eq!((nft_info.owner.as_str(), nft_info.equiped), ("owner", false));
+    let nft_info = nft_at(deps.as_ref(), 301).unwrap();
+    assert_eq!((nft_info.owner.as_str(), nft_info.equiped), ("new_owner", false));
+
+    assert_eq!(balance_at(deps.as_ref(), "owner", 100).balance, 0);
+    assert_eq!(balance_at(deps.as_ref(), "owner", 150), BalanceResponse { balance: 1, equipped: 1, unequipped: 0, revoked: 0 });
+    assert_eq!(balance_at(deps.as_ref(), "owner", 250), BalanceResponse { balance: 1, equipped: 0, unequipped: 1, revoked: 0 });
+    assert_eq!(balance_at(deps.as_ref(), "owner", 301).balance, 0);
+    assert_eq!(balance_at(deps.as_ref(), "new_owner", 300).balance, 0);
+    assert_eq!(balance_at(deps.as_ref(), "new_owner", 301).balance, 1);
+
+}
+
+// function for testing the history of a nft
+#[test]
+fn test_nft_history() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+
+    // helper to get an env at a given height
+    let env_at = |height: u64| {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    };
+
+    // mint, unequip, equip and unadmit a nft at increasing heights
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: Some("ipfs://nft_uri".to_string()),
+        metadata_hash: None,
+        class_id: None,
+        kind: None,
+    });
+    let steps = [
+        (MINTER, mint_msg),
+        ("owner", crate::msg::ExecuteMsg::UnEquip { nft_id: "nft_id".to_string() }),
+        ("owner", crate::msg::ExecuteMsg::Equip { nft_id: "nft_id".to_string() }),
+        (MINTER, crate::msg::ExecuteMsg::UnAdmit { nft_id: "nft_id".to_string() }),
+    ];
+    for (height, (sender, msg)) in (100..).zip(steps) {
+        let _res = contract
+            .execute(deps.as_mut(), env_at(height), mock_info(sender, &[]), msg)
+            .unwrap();
+    }
+
+    // helper to query the history
+    let query_history = |deps: cosmwasm_std::Deps, start_after: Option<u64>, limit: Option<u32>| -> Vec<HistoryEntry> {
+        let query_msg = crate::msg::QueryMsg::NftHistory { nft_id: "nft_id".to_string(), start_after, limit };
+        from_json(contract.query(deps, mock_env(), query_msg).unwrap()).unwrap()
+    };
+
+    // every change is recorded, oldest first
+    let history = query_history(deps.as_ref(), None, None);
+    let actions: Vec<(&str, &str, u64)> = history
+        .iter()
+        .map(|entry| (entry.action.as_str(), entry.actor.as_str(), entry.height))
+        .collect();
+    assert_eq!(
+        actions,
+        vec![("mint", MINTER, 100), ("unequip", "owner", 101), ("equip", "owner", 102), ("revoke", MINTER, 103)]
+    );
+    assert_eq!(history[0].time, mock_env().block.time);
+
+    // the history is paginated by sequence number
+    let page = query_history(deps.as_ref(), Some(1), Some(1));
+    assert_eq!(page.len(), 1);
+    assert_eq!((page[0].seq, page[0].action.as_str()), (2, "equip"));
+
+    // unknown nfts have no history
+    let query_msg = crate::msg::QueryMsg::NftHistory { nft_id: "unknown".to_string(), start_after: None, limit: None };
+    let history: Vec<HistoryEntry> = from_json(contract.query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+    assert!(history.is_empty());
+
+}
+
+// function for testing the destruction of a nft by its issuer
+#[test]
+fn test_destroy() {
+    // prepare the mock dependencies
+    let mut deps = mock_dependencies();
+
+    // setup the contract
+    setup_contract(deps.as_mut());
+    let contract = Aura4973::default();
+    let env = mock_env();
+
+    // the minter creates a badge class that a teacher may issue
+    let create_msg = crate::msg::ExecuteMsg::CreateBadgeClass(crate::msg::BadgeClassMsg {
+        id: "course_x".to_string(),
+        nft_uri: "ipfs://course_x".to_string(),
+        metadata_hash: None,
+        max_supply: None,
+        issuers: vec!["teacher".to_string()],
+        expires_after: None,
+        kind: None,
+    });
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), create_msg)
+        .unwrap();
+
+    // the teacher wrongly issues a nft
+    let mint_msg = crate::msg::ExecuteMsg::Mint(crate::msg::MintMsg {
+        nft_id: "nft_id".to_string(),
+        owner: "owner".to_string(),
+        nft_uri: None,
+        metadata_hash: None,
+        class_id: Some("course_x".to_string()),
+        kind: None,
+    });
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("teacher", &[]), mint_msg.clone())
+        .unwrap();
+
+    // only the issuer or the admin can destroy the nft
+    let destroy_msg = crate::msg::ExecuteMsg::Destroy { nft_id: "nft_id".to_string() };
+    for sender in ["owner", "random"] {
+        let err = contract
+            .execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), destroy_msg.clone())
+            .unwrap_err();
+        assert_eq!(err, crate::error::ContractError::Unauthorized {});
+    }
+
+    // the teacher destroys the nft
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("teacher", &[]), destroy_msg.clone())
+        .unwrap();
+    assert_eq!(res.events[0].ty, "abt_burn");
+    assert!(res.events[0].attributes.contains(&attr("state_before", "equipped")));
+    assert!(res.events[0].attributes.contains(&attr("state_after", "burned")));
+
+    // the nft is gone but its tombstone tells it existed
+    let query_msg = crate::msg::QueryMsg::NftInfo { nft_id: "nft_id".to_string() };
+    let _err = contract.query(deps.as_ref(), env.clone(), query_msg).unwrap_err();
+    let query_msg = crate::msg::QueryMsg::Tombstone { nft_id: "nft_id".to_string() };
+    let tombstone: Option<Tombstone> = from_json(contract.query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+    assert_eq!(
+        tombstone,
+        Some(Tombstone {
+            owner: "owner".to_string(),
+            issuer: "teacher".to_string(),
+            burned_at: env.block.time,
+            burned_by: Addr::unchecked("teacher"),
+        })
+    );
+    let query_msg = crate::msg::QueryMsg::Tombstone { nft_id: "unknown".to_string() };
+    let tombstone: Option<Tombstone> = from_json(contract.query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+    assert_eq!(tombstone, None);
+
+    // the counters, the holders and the history follow
+    let stats: StatsResponse = from_json(
+        contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::Stats {}).unwrap()
+    ).unwrap();
+    assert_eq!(stats, StatsResponse { issued: 1, equipped: 0, unequipped: 0, revoked: 0, burned: 1 });
+    let holders: Vec<String> = from_json(
+        contract.query(deps.as_ref(), env.clone(), crate::msg::QueryMsg::Holders { start_after: None, limit: None }).unwrap()
+    ).unwrap();
+    assert!(holders.is_empty());
+    let query_msg = crate::msg::QueryMsg::NftHistory { nft_id: "nft_id".to_string(), start_after: None, limit: None };
+    let history: Vec<HistoryEntry> = from_json(contract.query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+    assert_eq!(history.last().unwrap().action, "burn");
+
+    // the id can never be minted again
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("teacher", &[]), mint_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::NftDestroyed { nft_id: "nft_id".to_string() });
+
+    // an issuer removed from the class can no longer destroy what it issued, the admin still can
+    let mut mint_msg_2 = mint_msg;
+    if let crate::msg::ExecuteMsg::Mint(msg) = &mut mint_msg_2 {
+        msg.nft_id = "nft_id_2".to_string();
+    }
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("teacher", &[]), mint_msg_2)
+        .unwrap();
+    let issuers_msg = crate::msg::ExecuteMsg::UpdateBadgeClassIssuers {
+        class_id: "course_x".to_string(),
+        add: vec![],
+        remove: vec!["teacher".to_string()],
+    };
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), issuers_msg)
+        .unwrap();
+    let destroy_msg = crate::msg::ExecuteMsg::Destroy { nft_id: "nft_id_2".to_string() };
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("teacher", &[]), destroy_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, crate::error::ContractError::Unauthorized {});
+    let _res = contract
+        .execute(deps.as_mut(), env.clone(), mock_info(MINTER, &[]), destroy_msg)
+        .unwrap();
+
+}
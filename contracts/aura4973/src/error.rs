@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError, Timestamp};
+use cw_controllers::HookError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,6 +7,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Hook(#[from] HookError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -20,4 +24,148 @@ pub enum ContractError {
 
     #[error("NFT is already unadmitted")]
     NftAlreadyUnadmitted {},
+
+    #[error("Soulbound token: {action} is not supported")]
+    Soulbound { action: String },
+
+    #[error("Nft uri must not be empty")]
+    EmptyUri {},
+
+    #[error("Nft uri is {length} characters long, the maximum is {max_length}")]
+    UriTooLong { length: usize, max_length: u32 },
+
+    #[error("Uri scheme `{scheme}` is not allowed")]
+    UriSchemeNotAllowed { scheme: String },
+
+    #[error("Relative nft uri requires a base uri")]
+    MissingBaseUri {},
+
+    #[error("Uri must be an absolute uri")]
+    NotAbsoluteUri {},
+
+    #[error("Nft uri is required when minting without a badge class")]
+    MissingNftUri {},
+
+    #[error("Badge class id must not be empty")]
+    EmptyBadgeClassId {},
+
+    #[error("Badge class {class_id} already exists")]
+    BadgeClassExists { class_id: String },
+
+    #[error("Badge class {class_id} has reached its max supply")]
+    BadgeClassSupplyExhausted { class_id: String },
+
+    #[error("Badges of class {class_id} must carry the metadata of the class")]
+    BadgeClassMetadataMismatch { class_id: String },
+
+    #[error("Badges of class {class_id} must be of the kind of the class")]
+    BadgeClassKindMismatch { class_id: String },
+
+    #[error("Owner already holds a badge of kind {kind}")]
+    AlreadyHoldsBadge { kind: String },
+
+    #[error("Cannot recover nfts to the same address")]
+    RecoveryToSelf {},
+
+    #[error("A recovery of {from} is already pending")]
+    RecoveryPending { from: String },
+
+    #[error("Recovery cannot be executed before {executable_at}")]
+    RecoveryLocked { executable_at: Timestamp },
+
+    #[error("Recovery has not been confirmed by the new owner")]
+    RecoveryNotConfirmed {},
+
+    #[error("Threshold must be between 1 and the number of guardians")]
+    InvalidThreshold {},
+
+    #[error("Caller is not a guardian of the owner")]
+    NotGuardian {},
+
+    #[error("Guardian has already approved the recovery")]
+    AlreadyApproved {},
+
+    #[error("Guardian has not approved a recovery of the owner")]
+    NoApproval {},
+
+    #[error("No social recovery of the owner is pending")]
+    NoSocialRecovery {},
+
+    #[error("Recovery has not been approved by enough guardians")]
+    RecoveryNotApproved {},
+
+    #[error("Owner does not accept nfts from {issuer}")]
+    RecipientRefused { issuer: String },
+
+    #[error("Owner only accepts nfts it claims itself")]
+    ClaimRequired {},
+
+    #[error("Nft {nft_id} already exists")]
+    NftAlreadyExists { nft_id: String },
+
+    #[error("Nft {nft_id} has been destroyed, its id cannot be reused")]
+    NftDestroyed { nft_id: String },
+
+    #[error("Offer has expired")]
+    OfferExpired {},
+
+    #[error("Campaign {campaign_id} already exists")]
+    CampaignExists { campaign_id: String },
+
+    #[error("Nft id {nft_id} is reserved for claims of campaign {campaign_id}")]
+    NftIdReserved { nft_id: String, campaign_id: String },
+
+    #[error("Ids starting with {campaign_id}/ are already in use")]
+    CampaignIdsInUse { campaign_id: String },
+
+    #[error("Campaign needs a merkle root or secret code keys")]
+    MissingClaimCondition {},
+
+    #[error("Campaign must end after it starts")]
+    InvalidCampaignPeriod {},
+
+    #[error("Campaign has not started yet")]
+    CampaignNotStarted {},
+
+    #[error("Campaign has ended")]
+    CampaignEnded {},
+
+    #[error("Campaign has reached its max number of claims")]
+    CampaignCapReached {},
+
+    #[error("Hash must be a 32 byte sha256 digest, got {length} bytes")]
+    InvalidHash { length: usize },
+
+    #[error("Public key must be a 33 or 65 byte secp256k1 key, got {length} bytes")]
+    InvalidPublicKey { length: usize },
+
+    #[error("Invalid merkle proof or secret code signature")]
+    InvalidProof {},
+
+    #[error("Already claimed")]
+    AlreadyClaimed {},
+
+    #[error("Insufficient fee, {required} is required")]
+    InsufficientFee { required: Coin },
+
+    #[error("Duration of {seconds} seconds is longer than the maximum of {max_seconds}")]
+    DurationTooLong { seconds: u64, max_seconds: u64 },
+
+    #[error("Mint fee cannot be zero, unset it to make minting free")]
+    ZeroMintFee {},
+
+    #[error("Cannot migrate from a different contract: {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("Nfts are being reindexed after a migration, see ExecuteMsg::Reindex")]
+    ReindexPending {},
+
+    #[error("No reindex is pending")]
+    NoReindex {},
+
+    #[error("No admin is proposed")]
+    NoPendingAdmin {},
+
+    #[error("Metadata hash must be a 32 byte sha256 digest, got {length} bytes")]
+    InvalidMetadataHash { length: usize },
 }
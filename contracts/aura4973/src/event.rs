@@ -0,0 +1,41 @@
+use cosmwasm_std::{Addr, Env, Event};
+
+use crate::state::{NftInfo, NftStatus};
+
+/// Builds the `wasm-abt_<action>` event emitted for every state transition of a nft.
+/// The attributes are documented in the README and are part of the contract interface:
+/// indexers rely on them, so they must only ever be extended
+pub fn nft_event(
+    action: &str,
+    nft_info: &NftInfo,
+    sender: &Addr,
+    state_before: Option<NftStatus>,
+    env: &Env,
+) -> Event {
+    let state_before = state_before.map_or("none", |status| status.as_str());
+    status_event(action, nft_info, sender, state_before, nft_info.status().as_str(), env)
+}
+
+/// Event of a nft destroyed by its issuer, whose state after is `burned`
+pub fn burn_event(nft_info: &NftInfo, sender: &Addr, env: &Env) -> Event {
+    status_event("burn", nft_info, sender, nft_info.status().as_str(), "burned", env)
+}
+
+fn status_event(
+    action: &str,
+    nft_info: &NftInfo,
+    sender: &Addr,
+    state_before: &str,
+    state_after: &str,
+    env: &Env,
+) -> Event {
+    Event::new(format!("abt_{}", action))
+        .add_attribute("nft_id", &nft_info.id)
+        .add_attribute("owner", &nft_info.owner)
+        .add_attribute("sender", sender)
+        .add_attribute("state_before", state_before)
+        .add_attribute("state_after", state_after)
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("block_time", env.block.time.seconds().to_string())
+        .add_attribute("issuer", &nft_info.issuer)
+}
@@ -0,0 +1,66 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Binary, CosmosMsg, StdResult, WasmMsg};
+
+/// Change of a nft that is reported to the registered hooks
+#[cw_serde]
+pub enum AbtEvent {
+    Mint,
+    Unequip,
+    Equip,
+    Revoke,
+    Burn,
+    /// The nft was moved to a new owner by a recovery
+    Rebind,
+}
+
+/// AbtHookMsg is sent to every registered hook when a nft changes
+#[cw_serde]
+pub struct AbtHookMsg {
+    pub event: AbtEvent,
+    pub nft_id: String,
+    pub owner: String,
+    /// Owner of the nft before a `Rebind`, not set for other events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_owner: Option<String>,
+}
+
+impl AbtHookMsg {
+    pub fn new(event: AbtEvent, nft_id: impl Into<String>, owner: impl Into<String>) -> Self {
+        AbtHookMsg {
+            event,
+            nft_id: nft_id.into(),
+            owner: owner.into(),
+            previous_owner: None,
+        }
+    }
+
+    /// sets the owner of the nft before a rebind
+    pub fn with_previous_owner(mut self, previous_owner: impl Into<String>) -> Self {
+        self.previous_owner = Some(previous_owner.into());
+        self
+    }
+
+    /// serializes the message
+    pub fn into_binary(self) -> StdResult<Binary> {
+        let msg = AbtHookExecuteMsg::AbtHook(self);
+        to_json_binary(&msg)
+    }
+
+    /// creates a cosmos_msg sending this struct to the named contract
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let msg = self.into_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// This is just a helper to properly serialize the above message.
+/// Hook contracts must handle the `abt_hook` variant in their `ExecuteMsg`
+#[cw_serde]
+pub enum AbtHookExecuteMsg {
+    AbtHook(AbtHookMsg),
+}
@@ -1,7 +1,10 @@
 mod contract_test;
 pub mod contract;
 mod error;
+pub mod event;
+pub mod hook;
 pub mod msg;
+pub mod receiver;
 pub mod state;
 
 pub use crate::error::ContractError;
@@ -1,4 +1,8 @@
 use cosmwasm_schema::{cw_serde};
+use cosmwasm_std::{Binary, Coin, HexBinary, Timestamp};
+use cw_utils::Expiration;
+
+use crate::state::{ReceivePolicy, RecoveryConfig, UriConfig};
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
@@ -12,22 +16,197 @@ pub struct InstantiateMsg {
     /// This is designed for a base NFT that is controlled by an external program
     /// or contract. You will likely replace this with custom logic in custom NFTs
     pub minter: String,
+
+    /// The admin manages the contract configuration, including who the minter is.
+    /// Defaults to the minter, in which case a leaked minter key also gives away the
+    /// administration of the contract. A separate admin is recommended, it can be
+    /// rotated later with `ExecuteMsg::ProposeAdmin`
+    pub admin: Option<String>,
+
+    /// Rules for `nft_uri` values. Defaults to ipfs, https and ar uris of at most
+    /// 512 characters, without a base uri
+    pub uri_config: Option<UriConfig>,
+
+    /// Rules for recovering the nfts of a lost address. Defaults to a delay of
+    /// three days and a confirmation by the new address
+    pub recovery_config: Option<RecoveryConfig>,
+
+    /// Fee paid for every minted or claimed nft. Minting is free if not set
+    pub mint_fee: Option<Coin>,
+
+    /// Default recipient of withdrawn fees. Defaults to the admin
+    pub treasury: Option<String>,
+}
+
+/// Parameters of a newly minted NFT
+#[cw_serde]
+pub struct MintMsg {
+    /// Unique ID of the NFT
+    pub nft_id: String,
+    /// The owner of the newly minter NFT
+    pub owner: String,
+    /// Universal resource identifier for this NFT
+    /// Should point to a JSON file that conforms to the ERC721
+    /// Metadata JSON Schema. May be a path relative to the configured base uri.
+    /// Required unless the NFT is a badge of a class, whose NFTs carry the metadata
+    /// of the class: if given, it must then be the uri of the class
+    pub nft_uri: Option<String>,
+    /// Optional sha256 digest of the metadata JSON that `nft_uri` points to.
+    /// Lets verifiers detect that the off-chain content has been swapped.
+    /// If given for a badge of a class, it must be the hash of the class
+    pub metadata_hash: Option<HexBinary>,
+    /// Badge class the NFT is an assertion of. Issuers of the class may mint it
+    pub class_id: Option<String>,
+    /// Kind of credential. An owner holds at most one NFT of each unique kind.
+    /// Only the minter may set the kind of a NFT without badge class. Badges of a class
+    /// are of the kind of the class: if given, it must be the kind of the class
+    pub kind: Option<String>,
+}
+
+/// Parameters of a NFT minted to a contract that is notified of it
+#[cw_serde]
+pub struct MintAndNotifyMsg {
+    /// Unique ID of the NFT
+    pub nft_id: String,
+    /// The contract owning the newly minted NFT. Must handle `ReceiveAbt`
+    pub owner: String,
+    /// Universal resource identifier for this NFT, see `MintMsg`
+    pub nft_uri: Option<String>,
+    /// Optional sha256 digest of the metadata JSON that `nft_uri` points to
+    pub metadata_hash: Option<HexBinary>,
+    /// Badge class the NFT is an assertion of
+    pub class_id: Option<String>,
+    /// Kind of credential
+    pub kind: Option<String>,
+    /// Message forwarded to the owner in `AbtReceiveMsg`
+    pub msg: Binary,
+}
+
+/// Parameters of a bulk revocation of the NFTs of an issuer
+#[cw_serde]
+pub struct RevokeIssuedByMsg {
+    /// Address whose NFTs are revoked
+    pub issuer: String,
+    /// Only NFTs issued at or after this height are revoked
+    pub since_height: u64,
+    /// Id of the last NFT walked by the previous page
+    pub start_after: Option<String>,
+    /// Maximum number of NFTs walked by this page
+    pub limit: Option<u32>,
+    /// Reason stored on every revoked NFT
+    pub reason: String,
+}
+
+/// Parameters of a NFT offered to a recipient
+#[cw_serde]
+pub struct OfferMsg {
+    /// Unique ID of the NFT
+    pub nft_id: String,
+    /// The recipient of the offer and owner of the NFT once accepted
+    pub owner: String,
+    /// Universal resource identifier for this NFT, see `MintMsg`
+    pub nft_uri: Option<String>,
+    /// Optional sha256 digest of the metadata JSON that `nft_uri` points to
+    pub metadata_hash: Option<HexBinary>,
+    /// Badge class the NFT is an assertion of
+    pub class_id: Option<String>,
+    /// Kind of credential
+    pub kind: Option<String>,
+    /// The offer cannot be accepted after it expired. Never expires if not set
+    pub expires: Option<Expiration>,
+}
+
+/// Parameters of a new claim campaign
+#[cw_serde]
+pub struct CampaignMsg {
+    /// Unique ID of the campaign, claimed NFTs get the id `<campaign id>/<claim number>`
+    pub id: String,
+    /// Metadata uri of NFTs claimed with a secret code, checked when creating the campaign
+    pub nft_uri: Option<String>,
+    /// Optional sha256 digest of the metadata JSON that `nft_uri` points to
+    pub metadata_hash: Option<HexBinary>,
+    /// Badge class the claimed NFTs are assertions of
+    pub class_id: Option<String>,
+    /// Kind of credential of the claimed NFTs
+    pub kind: Option<String>,
+    /// Root of a merkle tree over the allowed claims, see `ClaimProof::Merkle`
+    pub merkle_root: Option<HexBinary>,
+    /// secp256k1 public keys of one-time secret codes, see `ClaimProof::Secret`
+    pub code_keys: Vec<HexBinary>,
+    /// Maximum number of claims, unlimited if not set
+    pub max_claims: Option<u64>,
+    /// NFTs cannot be claimed before this time
+    pub start: Option<Timestamp>,
+    /// NFTs cannot be claimed from this time on, must be after `start`
+    pub end: Option<Timestamp>,
+}
+
+/// Proof that the sender may claim a NFT of a campaign
+#[cw_serde]
+pub enum ClaimProof {
+    /// A leaf of the merkle tree of the campaign. Leaves are the sha256 of `claim_id`,
+    /// the address of the claimer and `nft_uri`, each prefixed with its length in bytes
+    /// as a 4 byte big endian integer. Pairs of nodes are sorted before hashing.
+    /// Only the address in the leaf can claim with it
+    Merkle {
+        claim_id: String,
+        nft_uri: String,
+        proof: Vec<HexBinary>,
+    },
+    /// A one-time secret code, which is the secp256k1 private key of a public key registered
+    /// with the campaign. The claimer signs the sha256 of the contract address, the campaign id
+    /// and its own address, prefixed with their lengths like the fields of merkle leaves.
+    /// The code itself is never broadcast and the signature is only valid for the claimer
+    Secret {
+        public_key: HexBinary,
+        signature: HexBinary,
+    },
+}
+
+/// Parameters of a new badge class
+#[cw_serde]
+pub struct BadgeClassMsg {
+    /// Unique ID of the badge class
+    pub id: String,
+    /// Metadata uri shared by the badges of the class
+    pub nft_uri: String,
+    /// Optional sha256 digest of the metadata JSON that `nft_uri` points to
+    pub metadata_hash: Option<HexBinary>,
+    /// Maximum number of badges of the class, unlimited if not set
+    pub max_supply: Option<u64>,
+    /// Addresses besides the minter that may mint badges of the class
+    pub issuers: Vec<String>,
+    /// Badges of the class expire this many seconds after minting, never if not set.
+    /// At most 100 years
+    pub expires_after: Option<u64>,
+    /// Kind of credential of the badges of the class
+    pub kind: Option<String>,
+}
+
+/// Collection-level metadata of the contract
+#[cw_serde]
+pub struct UpdateContractInfoMsg {
+    /// Description of the collection
+    pub description: Option<String>,
+    /// Image or logo of the collection
+    pub image: Option<String>,
+    /// Link to the website of the collection
+    pub external_url: Option<String>,
+    /// Display name of the issuing organisation
+    pub issuer_name: Option<String>,
+    /// Uri of a JSON document with further collection-level metadata
+    pub metadata_uri: Option<String>,
 }
 
 /// Message type for `execute` entry_point
 #[cw_serde]
 pub enum ExecuteMsg {
     /// @notice Mint a token to user
-    Mint{
-        /// Unique ID of the NFT
-        nft_id: String,
-        /// The owner of the newly minter NFT
-        owner: String,
-        /// Universal resource identifier for this NFT
-        /// Should point to a JSON file that conforms to the ERC721
-        /// Metadata JSON Schema
-        nft_uri: String,
-    },
+    Mint(MintMsg),
+
+    /// @notice Mint a token to a contract and call its `ReceiveAbt` entry with an
+    /// `AbtReceiveMsg`, the same way cw721 `SendNft` calls `ReceiveNft`.
+    MintAndNotify(MintAndNotifyMsg),
 
     /// @notice Removes the `String token_id` from an account.
     /// @dev Must emit a `event Transfer` with the `address to` field pointing to
@@ -44,11 +223,169 @@ pub enum ExecuteMsg {
     /// @notice un admit the `String token_id` from account.
     UnAdmit{ nft_id: String },
 
+    /// @notice destroy a NFT. A tombstone of it is kept and its id can never be minted again.
+    /// Only callable by the admin, or by the issuer of the NFT as long as it may still mint it.
+    Destroy{ nft_id: String },
+
+    /// @notice revoke every NFT issued by an address from a given height on, e.g. after its
+    /// key leaked. The NFTs are walked in pages: the response data is a `RevokeIssuedByResponse`
+    /// whose `next` is passed as `start_after` until it is empty. The issuer can still mint
+    /// afterwards: replace it with `UpdateMinter` or remove it with `UpdateBadgeClassIssuers`.
+    /// Only callable by the admin.
+    RevokeIssuedBy(RevokeIssuedByMsg),
+
+    /// @notice offer a NFT to a recipient. The NFT is minted only once the recipient
+    /// accepts it. Until the offer expires, its id cannot be minted or offered otherwise.
+    /// Callable by whoever may mint the NFT.
+    Offer(OfferMsg),
+
+    /// @notice accept an offer and mint its NFT. The recipient pays the mint fee.
+    /// Only callable by the recipient.
+    Accept{ nft_id: String },
+
+    /// @notice reject an offer. Only callable by the recipient.
+    Reject{ nft_id: String },
+
+    /// @notice withdraw an offer. Only callable by the issuer of the offer.
+    CancelOffer{ nft_id: String },
+
+    /// @notice create a campaign of NFTs that users claim for themselves. Claimed NFTs
+    /// get the ids `id/1`, `id/2`, ... and no other NFT can be minted or offered with
+    /// an id starting with `id/`. Callable by whoever may mint its NFTs.
+    CreateCampaign(CampaignMsg),
+
+    /// @notice claim a NFT of a campaign to the sender.
+    Claim{ campaign_id: String, proof: ClaimProof },
+
+    /// @notice request to move all NFTs of `from` to `to`, e.g. after `from` lost its key.
+    /// Executed after the configured delay, during which `from` can cancel it.
+    /// NFTs of a unique kind that `to` already holds are skipped and stay with `from`,
+    /// their ids are listed in the `skipped` attribute. Only callable by the minter.
+    Recover{ from: String, to: String },
+
+    /// @notice confirm a pending recovery. Only callable by its new owner.
+    ConfirmRecovery{ from: String },
+
+    /// @notice execute a pending recovery once its delay has passed and it is confirmed.
+    /// NFTs are moved in pages of 30: while the response has a `next` attribute,
+    /// the recovery stays pending and is continued by calling this again.
+    FinalizeRecovery{ from: String },
+
+    /// @notice cancel a pending recovery. Only callable by the old owner or the minter.
+    CancelRecovery{ from: String },
+
+    /// @notice replace the rules for recoveries. Only callable by the admin.
+    UpdateRecoveryConfig{ config: RecoveryConfig },
+
+    /// @notice designate the guardians that may jointly recover the NFTs of the sender.
+    /// An empty list removes the guardians. Cancels a pending social recovery.
+    SetGuardians{ guardians: Vec<String>, threshold: u32 },
+
+    /// @notice approve moving all NFTs of `owner` to `new_owner`. Only callable by a guardian
+    /// of `owner`. A guardian approves one new owner at a time: approving another one moves
+    /// its approval. Once `threshold` guardians approved the same new owner, the recovery to it
+    /// can be executed after the configured guardian delay, during which `owner` can cancel it.
+    ApproveSocialRecovery{ owner: String, new_owner: String },
+
+    /// @notice withdraw the approval of the sender for a recovery of `owner`. A recovery whose
+    /// new owner no longer has `threshold` approvals cannot be executed until it has them again.
+    WithdrawSocialRecoveryApproval{ owner: String },
+
+    /// @notice execute a social recovery once enough guardians approved and the delay has passed.
+    /// NFTs are moved in pages like `FinalizeRecovery` does.
+    ExecuteSocialRecovery{ owner: String },
+
+    /// @notice cancel the pending social recovery of the sender and clear every approval of its guardians.
+    CancelSocialRecovery{},
+
+    /// @notice choose from whom the sender accepts NFTs minted to its address.
+    SetReceivePolicy{ policy: ReceivePolicy },
+
+    /// @notice cw721 messages sent by wallets and marketplaces.
+    /// Account-bound tokens cannot be transferred or approved, so these always fail
+    /// with `ContractError::Soulbound`.
+    TransferNft{ recipient: String, token_id: String },
+    SendNft{ contract: String, token_id: String, msg: Binary },
+    Approve{ spender: String, token_id: String, expires: Option<Expiration> },
+    Revoke{ spender: String, token_id: String },
+    ApproveAll{ operator: String, expires: Option<Expiration> },
+    RevokeAll{ operator: String },
+
+    /// @notice set the fee paid for every minted or claimed NFT, or make minting free
+    /// if `fee` is not set, and the default recipient of withdrawn fees.
+    /// Only callable by the admin.
+    UpdateMintFee{ fee: Option<Coin>, treasury: String },
+
+    /// @notice send collected fees to `to`, or to the treasury if not set.
+    /// Only callable by the admin.
+    Withdraw{ amount: Coin, to: Option<String> },
+
+    /// @notice reindex the next `limit` NFTs after a migration. Migrating from a version
+    /// without counters reindexes the first page, every other message is rejected until
+    /// the last page is reindexed. Only callable by the admin.
+    Reindex{ limit: Option<u32> },
+
+    /// @notice register a contract that is notified with an `AbtHookMsg` when a NFT
+    /// is minted, equipped, unequipped, unadmitted, burned or moved to a new owner by a
+    /// recovery. Only callable by the admin.
+    AddHook{ addr: String },
+
+    /// @notice unregister a hook contract. Only callable by the admin.
+    RemoveHook{ addr: String },
+
+    /// @notice replace the rules for `nft_uri` values. Only callable by the admin.
+    UpdateUriConfig{ config: UriConfig },
+
+    /// @notice register a new badge class. Only callable by the admin.
+    CreateBadgeClass(BadgeClassMsg),
+
+    /// @notice add or remove addresses that may mint badges of a class.
+    /// Only callable by the admin.
+    UpdateBadgeClassIssuers{ class_id: String, add: Vec<String>, remove: Vec<String> },
+
+    /// @notice replace the minter, e.g. after its key leaked. Only callable by the admin.
+    UpdateMinter{ minter: String },
+
+    /// @notice propose a new admin, who takes over once it calls `AcceptAdmin`.
+    /// Proposing again replaces the proposed admin. Only callable by the admin.
+    ProposeAdmin{ admin: String },
+
+    /// @notice become the admin of the contract. Only callable by the proposed admin.
+    AcceptAdmin{},
+
+    /// @notice add or remove kinds of which an owner may hold at most one NFT.
+    /// Only callable by the admin.
+    UpdateUniqueKinds{ add: Vec<String>, remove: Vec<String> },
+
+    /// @notice replace the collection-level metadata returned by `ContractInfo`.
+    /// Name and symbol cannot be changed. Only callable by the admin.
+    UpdateContractInfo(UpdateContractInfoMsg),
+
+}
+
+/// Boolean expression over the nfts of an owner, see `QueryMsg::Holds`.
+/// A nft counts only if it is equipped, not revoked and not expired
+#[cw_serde]
+pub enum Requirement {
+    /// The owner holds the nft with this id
+    Token { nft_id: String },
+    /// The owner holds a nft of this kind
+    Kind { kind: String },
+    /// Every requirement is met. The list cannot be empty
+    AllOf { requirements: Vec<Requirement> },
+    /// At least one requirement is met. The list cannot be empty
+    AnyOf { requirements: Vec<Requirement> },
+    /// At least `n` requirements are met, `n` is between one and the number of requirements
+    NOf { n: u32, requirements: Vec<Requirement> },
 }
 
 /// Message type for `migrate` entry_point
 #[cw_serde]
-pub enum MigrateMsg {}
+pub struct MigrateMsg {
+    /// Replaces the admin of the contract. Contracts migrated from a version without
+    /// admin are otherwise administered by the minter
+    pub admin: Option<String>,
+}
 
 /// Message type for `query` entry_point
 #[cw_serde]
@@ -57,9 +394,41 @@ pub enum QueryMsg {
     /// Returns top-level metadata about the contract: `ContractInfoResponse`
     ContractInfo {},
 
+    /// Returns the admin of the contract: `AdminResponse`
+    Admin {},
+
+    /// Returns the rules applied to `nft_uri` values: `UriConfig`
+    UriConfig {},
+
+    /// Returns the fee paid for every minted or claimed nft: `MintFeeResponse`
+    MintFee {},
+
+    /// Returns the contracts notified when a nft changes: `HooksResponse`
+    Hooks {},
+
     /// Total number of nfts issued
     NumNfts {},
 
+    /// Number of nfts in each status: `StatsResponse`
+    Stats {},
+
+    /// Number of nfts of an owner in each status: `BalanceResponse`
+    BalanceOf { owner: String },
+
+    /// Number of nfts of an owner in each status at the start of the block at the given height:
+    /// `BalanceResponse`
+    BalanceAtHeight { owner: String, height: u64 },
+
+    /// Returns the addresses holding at least one nft, revoked ones included, ordered by address.
+    /// Return type: `Vec<String>`
+    Holders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Number of addresses holding at least one nft: `NumHoldersResponse`
+    NumHolders {},
+
     /// Return the owner of the given nft, error if nft does not exist
     /// Return type: OwnerOfResponse
     OwnerOf {
@@ -68,13 +437,47 @@ pub enum QueryMsg {
 
     /// With MetaData Extension.
     /// Returns metadata about one particular nft, based on *ERC721 Metadata JSON Schema*
-    /// but directly from the contract: `NftInfoResponse`
+    /// but directly from the contract: `NftInfoResponse`.
+    /// Relative `nft_uri` values are returned resolved against the base uri the nft was minted with
     NftInfo {
         nft_id: String,
     },
 
+    /// Returns the changes of a nft, oldest first.
+    /// Return type: `Vec<HistoryEntry>`
+    NftHistory {
+        nft_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the record of a destroyed nft, null if no nft with this id was destroyed.
+    /// Return type: `Option<Tombstone>`
+    Tombstone {
+        nft_id: String,
+    },
+
+    /// With MetaData Extension.
+    /// Returns the nft as it was at the start of the block at the given height,
+    /// null if it did not exist yet.
+    /// Return type: `Option<NftInfo>`
+    NftInfoAtHeight {
+        nft_id: String,
+        height: u64,
+    },
+
+    /// With MetaData Extension.
+    /// Returns the metadata of several nfts at once, in the order of the given ids.
+    /// Ids of no nft get an entry without info instead of failing the query.
+    /// At most 30 ids can be given.
+    /// Return type: `Vec<NftInfoEntry>`
+    NftInfos {
+        nft_ids: Vec<String>,
+    },
+
     /// With MetaData Extension.
     /// Returns the list of all nft of a owner with the equipment status is true.
+    /// Expired nfts are listed too, see `expires_at` or use `Holds` to check validity.
     /// Return type: `AllNftOfResponse`
     AllEquippedNftOf {
         owner: String,
@@ -86,4 +489,120 @@ pub enum QueryMsg {
     AllUnequippedNftOf {
         owner: String,
     },
+
+    /// Returns the badge class with the given id: `BadgeClass`
+    BadgeClass {
+        class_id: String,
+    },
+
+    /// Returns the badge classes ordered by id.
+    /// Return type: `Vec<BadgeClass>`
+    BadgeClasses {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// With MetaData Extension.
+    /// Returns the nfts minted as assertions of the given badge class, ordered by id.
+    /// Return type: `Vec<NftInfo>`
+    NftsOfClass {
+        class_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// With MetaData Extension.
+    /// Returns the nfts issued by the given address, ordered by id.
+    /// Return type: `Vec<NftInfo>`
+    TokensByIssuer {
+        issuer: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the kinds of which an owner may hold at most one nft.
+    /// Return type: `Vec<String>`
+    UniqueKinds {},
+
+    /// Check whether the valid nfts of the owner meet the requirement.
+    /// The requirement may have at most 30 nodes.
+    /// Return type: `HoldsResponse`
+    Holds {
+        owner: String,
+        requirement: Requirement,
+    },
+
+    /// Check whether the owner holds a nft of the given kind that has not been unadmitted
+    /// and has not expired.
+    /// Return type: `HasBadgeResponse`
+    HasBadge {
+        owner: String,
+        kind: String,
+    },
+
+    /// Returns the rules for recoveries: `RecoveryConfig`
+    RecoveryConfig {},
+
+    /// Returns the pending recovery of the given address if any.
+    /// Return type: `Option<PendingRecovery>`
+    PendingRecovery {
+        from: String,
+    },
+
+    /// Returns the guardians of the given owner if any.
+    /// Return type: `Option<GuardianSet>`
+    Guardians {
+        owner: String,
+    },
+
+    /// Returns the pending social recovery of the given owner if any.
+    /// Return type: `Option<SocialRecovery>`
+    PendingSocialRecovery {
+        owner: String,
+    },
+
+    /// Returns the approvals of the guardians of the given owner, grouped by new owner.
+    /// Return type: `Vec<SocialRecoveryApprovals>`
+    SocialRecoveryApprovals {
+        owner: String,
+    },
+
+    /// Returns from whom the given owner accepts nfts: `ReceivePolicy`
+    ReceivePolicy {
+        owner: String,
+    },
+
+    /// Returns the pending offer of the given nft: `Offer`
+    Offer {
+        nft_id: String,
+    },
+
+    /// Returns the pending offers to a recipient ordered by nft id.
+    /// Return type: `Vec<Offer>`
+    OffersByRecipient {
+        recipient: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the claim campaign with the given id: `Campaign`
+    Campaign {
+        campaign_id: String,
+    },
+
+    /// Check whether a claim of a campaign was used. The claim key is the claim id
+    /// of a merkle claim or the hex encoded public key of a secret code.
+    /// Return type: `IsClaimedResponse`
+    IsClaimed {
+        campaign_id: String,
+        claim_key: String,
+    },
+
+    /// Check whether `content_hash` (sha256 of the fetched metadata) matches the
+    /// hash committed at mint time. Nfts minted without a hash never verify.
+    /// Return type: `VerifyMetadataResponse`
+    VerifyMetadata {
+        nft_id: String,
+        content_hash: HexBinary,
+    },
 }
@@ -2,9 +2,11 @@ use cosmwasm_schema::cw_serde;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, StdResult, Storage};
+use cosmwasm_std::{Addr, Coin, Empty, HexBinary, OverflowError, OverflowOperation, StdError, StdResult, Storage, Timestamp};
 
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use cw_controllers::Hooks;
+use cw_storage_plus::{Index, IndexList, IndexedMap, IndexedSnapshotMap, Item, Map, MultiIndex, SnapshotMap, Strategy};
+use cw_utils::Expiration;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct NftInfo {
@@ -13,19 +15,321 @@ pub struct NftInfo {
     pub nft_uri: String,
     pub equiped: bool,
     pub is_admitted: bool,
+    /// sha256 of the off-chain metadata, committed by the minter
+    pub metadata_hash: Option<HexBinary>,
+    /// Badge class the nft is an assertion of
+    pub class_id: Option<String>,
+    /// Time after which the nft is no longer valid
+    pub expires_at: Option<Timestamp>,
+    /// Kind of credential, e.g. a membership or proof of personhood
+    pub kind: Option<String>,
+    /// Address that issued the nft. Empty for nfts minted before it was recorded,
+    /// until the contract is migrated
+    #[serde(default)]
+    pub issuer: String,
+    /// Height of the block the nft was issued in. Zero for nfts minted before it was recorded
+    #[serde(default)]
+    pub issued_at_height: u64,
+    /// Why the nft was revoked in bulk, see `ExecuteMsg::RevokeIssuedBy`
+    #[serde(default)]
+    pub revoke_reason: Option<String>,
+    /// Version of the base uri that a relative `nft_uri` is stored with. Queries return
+    /// the `nft_uri` resolved against it and leave this unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_uri_version: Option<u64>,
+}
+
+impl NftInfo {
+    /// Returns whether the nft is equipped, not revoked and not expired at the given time
+    pub fn is_valid_at(&self, time: Timestamp) -> bool {
+        self.status() == NftStatus::Equipped && !self.is_expired_at(time)
+    }
+
+    /// Returns whether the nft has expired at the given time
+    pub fn is_expired_at(&self, time: Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| time >= expires_at)
+    }
+
+    /// Returns the status of the nft, revocation taking precedence over equipping
+    pub fn status(&self) -> NftStatus {
+        if self.is_admitted {
+            NftStatus::Revoked
+        } else if self.equiped {
+            NftStatus::Equipped
+        } else {
+            NftStatus::Unequipped
+        }
+    }
+}
+
+/// Status of a nft
+#[cw_serde]
+#[derive(Copy)]
+pub enum NftStatus {
+    Equipped,
+    Unequipped,
+    Revoked,
+}
+
+impl NftStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NftStatus::Equipped => "equipped",
+            NftStatus::Unequipped => "unequipped",
+            NftStatus::Revoked => "revoked",
+        }
+    }
+}
+
+/// Number of nfts in each status
+#[cw_serde]
+#[derive(Default)]
+pub struct NftCounts {
+    pub equipped: u64,
+    pub unequipped: u64,
+    pub revoked: u64,
+}
+
+impl NftCounts {
+    pub fn total(&self) -> u64 {
+        self.equipped + self.unequipped + self.revoked
+    }
+
+    /// Adds one nft of the given status, or removes it if `increment` is false.
+    /// Removing a nft that was not counted is an error
+    pub fn update(&mut self, status: NftStatus, increment: bool) -> StdResult<()> {
+        let count = match status {
+            NftStatus::Equipped => &mut self.equipped,
+            NftStatus::Unequipped => &mut self.unequipped,
+            NftStatus::Revoked => &mut self.revoked,
+        };
+        *count = if increment { increment_count(*count)? } else { decrement_count(*count)? };
+        Ok(())
+    }
+}
+
+/// A class of identical badges. Nfts minted with a class id carry its metadata.
+/// Like every nft of this contract, badges of a class can never be transferred
+#[cw_serde]
+pub struct BadgeClass {
+    pub id: String,
+    /// Metadata uri of the nfts of the class
+    pub nft_uri: String,
+    /// Version of the base uri that a relative `nft_uri` is stored with, see `NftInfo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_uri_version: Option<u64>,
+    /// sha256 of the off-chain metadata of the class
+    pub metadata_hash: Option<HexBinary>,
+    /// Maximum number of nfts of the class, unlimited if not set
+    pub max_supply: Option<u64>,
+    /// Number of nfts of the class minted so far
+    pub minted: u64,
+    /// Addresses besides the minter that may mint nfts of the class
+    pub issuers: Vec<Addr>,
+    /// Nfts of the class expire this many seconds after minting, never if not set
+    pub expires_after: Option<u64>,
+    /// Kind of credential of the nfts of the class
+    pub kind: Option<String>,
+}
+
+/// Rules for moving the nfts of a lost address to a new one
+#[cw_serde]
+pub struct RecoveryConfig {
+    /// Seconds between a recovery request and its execution, during which
+    /// the old owner can cancel it. At most 100 years
+    pub delay: u64,
+    /// Whether the new address must confirm the recovery before it is executed
+    pub require_confirmation: bool,
+    /// Seconds between the approval of a social recovery by enough guardians
+    /// and its execution, during which the owner can cancel it. At most 100 years
+    pub guardian_delay: u64,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            delay: 3 * 24 * 60 * 60,
+            require_confirmation: true,
+            guardian_delay: 3 * 24 * 60 * 60,
+        }
+    }
+}
+
+/// A recovery requested by the minter that has not been executed yet
+#[cw_serde]
+pub struct PendingRecovery {
+    pub from: Addr,
+    pub to: Addr,
+    /// Time after which the recovery can be executed
+    pub executable_at: Timestamp,
+    /// Whether `to` has confirmed the recovery
+    pub confirmed: bool,
+    /// Id of the last nft moved so far, set once the first page of the recovery is executed
+    pub start_after: Option<String>,
+}
+
+/// Addresses designated by an owner to jointly recover its nfts
+#[cw_serde]
+pub struct GuardianSet {
+    pub guardians: Vec<Addr>,
+    /// Number of guardians that must approve a recovery
+    pub threshold: u32,
+}
+
+/// A recovery approved by enough guardians of an owner that has not been executed yet
+#[cw_serde]
+pub struct SocialRecovery {
+    pub owner: Addr,
+    pub new_owner: Addr,
+    /// Guardians that approved the new owner
+    pub approvals: Vec<Addr>,
+    /// Time after which the recovery can be executed
+    pub executable_at: Timestamp,
+    /// Id of the last nft moved so far, set once the first page of the recovery is executed
+    pub start_after: Option<String>,
+}
+
+/// Guardians of an owner that approved moving its nfts to the same new owner
+#[cw_serde]
+pub struct SocialRecoveryApprovals {
+    pub new_owner: Addr,
+    pub guardians: Vec<Addr>,
+}
+
+/// From whom an owner accepts nfts pushed to its address
+#[cw_serde]
+pub enum ReceivePolicy {
+    /// Accept nfts from every issuer
+    AcceptAll,
+    /// Only accept nfts from the listed issuers
+    AllowList(Vec<String>),
+    /// Accept nfts from every issuer but the listed ones
+    BlockList(Vec<String>),
+    /// Refuse every pushed nft, only accept nfts the owner claims itself
+    RequireClaim,
+}
+
+/// A nft offered to a recipient, minted only once the recipient accepts it
+#[cw_serde]
+pub struct Offer {
+    pub nft_id: String,
+    /// The recipient of the offer and owner of the nft once accepted
+    pub owner: String,
+    /// Metadata of the nft, checked when the offer was made
+    pub nft_uri: String,
+    /// Version of the base uri that a relative `nft_uri` is stored with, see `NftInfo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_uri_version: Option<u64>,
+    pub metadata_hash: Option<HexBinary>,
+    pub class_id: Option<String>,
+    pub kind: Option<String>,
+    /// Address that made the offer and issues the nft
+    pub issuer: Addr,
+    /// The offer cannot be accepted after it expired
+    pub expires: Expiration,
+}
+
+/// A campaign of nfts that users claim for themselves, e.g. attendance badges of an event
+#[cw_serde]
+pub struct Campaign {
+    pub id: String,
+    /// Address that created the campaign and issues the claimed nfts
+    pub issuer: Addr,
+    /// Metadata uri of nfts claimed with a secret code, checked when the campaign was created
+    pub nft_uri: Option<String>,
+    /// Version of the base uri that a relative `nft_uri` is stored with, see `NftInfo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_uri_version: Option<u64>,
+    pub metadata_hash: Option<HexBinary>,
+    pub class_id: Option<String>,
+    pub kind: Option<String>,
+    /// Root of a merkle tree over the allowed claims, see `ClaimProof::Merkle`
+    pub merkle_root: Option<HexBinary>,
+    /// Maximum number of claims, unlimited if not set
+    pub max_claims: Option<u64>,
+    /// Number of nfts claimed so far
+    pub claimed: u64,
+    /// Nfts cannot be claimed before this time
+    pub start: Option<Timestamp>,
+    /// Nfts cannot be claimed from this time on
+    pub end: Option<Timestamp>,
+}
+
+pub struct OfferIndexes<'a>{
+    // String (recipient's address) -> String (nft_id)
+    pub recipient: MultiIndex<'a, String, Offer, String>,
 }
 
 pub struct NftIndexes<'a>{
     // String (owner's address) -> String (token_id)
     // String (token_id) -> TokenInfo (token_info)
     pub owner: MultiIndex<'a, String, NftInfo, String>,
+    // String (badge class id) -> String (token_id)
+    pub class: MultiIndex<'a, String, NftInfo, String>,
+    // (String, String) (owner's address, kind) -> String (token_id)
+    pub owner_kind: MultiIndex<'a, (String, String), NftInfo, String>,
+    // (String, String) (owner's address, status) -> String (token_id)
+    pub owner_status: MultiIndex<'a, (String, String), NftInfo, String>,
+    // String (issuer's address) -> String (token_id)
+    pub issuer: MultiIndex<'a, String, NftInfo, String>,
 }
 
 pub struct Aura4973 <'a>{
     pub contract_info: Item<'a, ContractInfoResponse>,
     pub minter: Item<'a, Addr>,
+    pub admin: Item<'a, Addr>,
+    // admin proposed by the current admin, it becomes the admin once it accepts
+    pub pending_admin: Item<'a, Addr>,
+    pub uri_config: Item<'a, UriConfig>,
+    // u64 (version) -> base uri, a new version is kept whenever the base uri changes
+    pub base_uris: Map<'a, u64, String>,
     pub nft_count: Item<'a, u64>,
-    pub nfts: IndexedMap<'a, &'a str, NftInfo, NftIndexes<'a>>,
+    // keeps the changes of every block to answer queries at past heights
+    pub nfts: IndexedSnapshotMap<'a, &'a str, NftInfo, NftIndexes<'a>>,
+    pub badge_classes: Map<'a, &'a str, BadgeClass>,
+    // kinds of which an owner may hold at most one nft
+    pub unique_kinds: Map<'a, &'a str, Empty>,
+    pub recovery_config: Item<'a, RecoveryConfig>,
+    // Addr (old owner) -> PendingRecovery
+    pub pending_recoveries: Map<'a, &'a Addr, PendingRecovery>,
+    // Addr (owner) -> GuardianSet
+    pub guardians: Map<'a, &'a Addr, GuardianSet>,
+    // Addr (owner) -> SocialRecovery, set while a new owner has enough approvals
+    pub social_recoveries: Map<'a, &'a Addr, SocialRecovery>,
+    // (Addr (owner), Addr (new owner)) -> guardians that approved the new owner.
+    // A guardian approves at most one new owner of an owner
+    pub social_recovery_approvals: Map<'a, (&'a Addr, &'a Addr), Vec<Addr>>,
+    // Addr (owner) -> ReceivePolicy, owners without entry accept all nfts
+    pub receive_policies: Map<'a, &'a Addr, ReceivePolicy>,
+    pub offers: IndexedMap<'a, &'a str, Offer, OfferIndexes<'a>>,
+    pub campaigns: Map<'a, &'a str, Campaign>,
+    // (campaign id, public key of a secret code) -> Empty
+    pub campaign_codes: Map<'a, (&'a str, &'a [u8]), Empty>,
+    // (campaign id, claim key) -> Empty, the claim key is the claim id of a merkle
+    // claim or the hex encoded public key of a secret code
+    pub claims: Map<'a, (&'a str, &'a str), Empty>,
+    // fee paid for every minted or claimed nft, minting is free if not set
+    pub mint_fee: Item<'a, Coin>,
+    // default recipient of withdrawn fees
+    pub treasury: Item<'a, Addr>,
+    // contracts notified when a nft changes
+    pub hooks: Hooks<'a>,
+    // number of nfts in each status
+    pub nft_counts: Item<'a, NftCounts>,
+    // String (owner's address) -> number of nfts of the owner in each status.
+    // Only holders of at least one nft have an entry
+    pub balances: SnapshotMap<'a, &'a str, NftCounts>,
+    // number of entries of balances
+    pub num_holders: Item<'a, u64>,
+    // (String (token_id), u64 (seq)) -> HistoryEntry
+    pub history: Map<'a, (&'a str, u64), HistoryEntry>,
+    // String (token_id) -> Tombstone of a destroyed nft
+    pub tombstones: Map<'a, &'a str, Tombstone>,
+    // number of destroyed nfts
+    pub num_burned: Item<'a, u64>,
+    // id of the last nft reindexed after a migration, None before the first page.
+    // Only set while the reindex is running
+    pub reindex: Item<'a, Option<String>>,
 }
 
 impl Default for Aura4973<'static>{
@@ -50,12 +354,56 @@ impl<'a> Aura4973<'a>{
     ) -> Self {
         let indexes = NftIndexes {
             owner: MultiIndex::new(token_owner_idx, tokens_key, tokens_owner_key),
+            class: MultiIndex::new(token_class_idx, tokens_key, "tokens__class"),
+            owner_kind: MultiIndex::new(token_owner_kind_idx, tokens_key, "tokens__owner_kind"),
+            owner_status: MultiIndex::new(token_owner_status_idx, tokens_key, "tokens__owner_status"),
+            issuer: MultiIndex::new(token_issuer_idx, tokens_key, "tokens__issuer"),
+        };
+        let offer_indexes = OfferIndexes {
+            recipient: MultiIndex::new(offer_recipient_idx, "offers", "offers__recipient"),
         };
         Self {
             contract_info: Item::new(contract_key),
             minter: Item::new(minter_key),
+            admin: Item::new("admin"),
+            pending_admin: Item::new("pending_admin"),
+            uri_config: Item::new("uri_config"),
+            base_uris: Map::new("base_uris"),
             nft_count: Item::new(token_count_key),
-            nfts: IndexedMap::new(tokens_key, indexes),
+            nfts: IndexedSnapshotMap::new(
+                tokens_key,
+                "tokens__checkpoints",
+                "tokens__changelog",
+                Strategy::EveryBlock,
+                indexes,
+            ),
+            badge_classes: Map::new("badge_classes"),
+            unique_kinds: Map::new("unique_kinds"),
+            recovery_config: Item::new("recovery_config"),
+            pending_recoveries: Map::new("pending_recoveries"),
+            guardians: Map::new("guardians"),
+            social_recoveries: Map::new("social_recoveries"),
+            social_recovery_approvals: Map::new("social_recovery_approvals"),
+            receive_policies: Map::new("receive_policies"),
+            offers: IndexedMap::new("offers", offer_indexes),
+            campaigns: Map::new("campaigns"),
+            campaign_codes: Map::new("campaign_codes"),
+            claims: Map::new("claims"),
+            mint_fee: Item::new("mint_fee"),
+            treasury: Item::new("treasury"),
+            hooks: Hooks::new("hooks"),
+            nft_counts: Item::new("nft_counts"),
+            balances: SnapshotMap::new(
+                "balances",
+                "balances__checkpoints",
+                "balances__changelog",
+                Strategy::EveryBlock,
+            ),
+            num_holders: Item::new("num_holders"),
+            history: Map::new("history"),
+            tombstones: Map::new("tombstones"),
+            num_burned: Item::new("num_burned"),
+            reindex: Item::new("reindex"),
         }
     }
 
@@ -69,12 +417,129 @@ impl<'a> Aura4973<'a>{
         Ok(val)
     }
 
+    /// Saves a nft at the given height, keeping the counters in line with its previous version
+    /// if it had one. Every change of a nft must go through this function
+    pub fn save_nft(&self, storage: &mut dyn Storage, old: Option<&NftInfo>, nft_info: &NftInfo, height: u64) -> StdResult<()> {
+        if let Some(old) = old {
+            self.update_counts(storage, old, false, height)?;
+        }
+        self.update_counts(storage, nft_info, true, height)?;
+        self.nfts.replace(storage, &nft_info.id, Some(nft_info), old, height)
+    }
+
+    /// Counts a nft in the global and owner counters, or uncounts it if `increment` is false
+    pub fn update_counts(&self, storage: &mut dyn Storage, nft_info: &NftInfo, increment: bool, height: u64) -> StdResult<()> {
+        let status = nft_info.status();
+
+        let mut counts = self.nft_counts.may_load(storage)?.unwrap_or_default();
+        counts.update(status, increment)?;
+        self.nft_counts.save(storage, &counts)?;
+
+        let mut balance = self.balances.may_load(storage, &nft_info.owner)?.unwrap_or_default();
+        let was_holder = balance.total() > 0;
+        balance.update(status, increment)?;
+        if balance.total() == 0 {
+            self.balances.remove(storage, &nft_info.owner, height)?;
+        } else {
+            self.balances.save(storage, &nft_info.owner, &balance, height)?;
+        }
+
+        // an owner becomes a holder with its first nft and stops being one with its last
+        let is_holder = balance.total() > 0;
+        if was_holder != is_holder {
+            let num_holders = self.num_holders.may_load(storage)?.unwrap_or_default();
+            let num_holders = if is_holder { increment_count(num_holders)? } else { decrement_count(num_holders)? };
+            self.num_holders.save(storage, &num_holders)?;
+        }
+        Ok(())
+    }
+
 }
     
 
 impl<'a> IndexList<NftInfo> for NftIndexes<'a>{
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<NftInfo>> + '_> {
-        let v: Vec<&dyn Index<NftInfo>> = vec![&self.owner];
+        let v: Vec<&dyn Index<NftInfo>> = vec![&self.owner, &self.class, &self.owner_kind, &self.owner_status, &self.issuer];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Rules applied to `nft_uri` when minting
+#[cw_serde]
+pub struct UriConfig {
+    /// Schemes an absolute uri may use, without the `://` separator
+    pub allowed_schemes: Vec<String>,
+    /// Maximum length of the `nft_uri` given when minting
+    pub max_length: u32,
+    /// If set, nfts may be minted with a path relative to this uri. Nfts keep the base
+    /// uri they were minted with, changing it does not affect existing nfts
+    pub base_uri: Option<String>,
+}
+
+impl Default for UriConfig {
+    fn default() -> Self {
+        Self {
+            allowed_schemes: vec!["ipfs".to_string(), "https".to_string(), "ar".to_string()],
+            max_length: 512,
+            base_uri: None,
+        }
+    }
+}
+
+impl UriConfig {
+    /// Returns the full uri of a given `nft_uri`, prefixing relative paths with `base_uri`
+    pub fn resolve(&self, nft_uri: &str) -> String {
+        match (&self.base_uri, uri_scheme(nft_uri)) {
+            (Some(base_uri), None) => format!("{}{}", base_uri, nft_uri),
+            _ => nft_uri.to_string(),
+        }
+    }
+}
+
+/// Adds one to a counter, failing on overflow
+pub fn increment_count(count: u64) -> StdResult<u64> {
+    count
+        .checked_add(1)
+        .ok_or_else(|| StdError::overflow(OverflowError::new(OverflowOperation::Add, count, 1)))
+}
+
+/// Removes one from a counter, failing instead of wrapping if it is already zero
+pub fn decrement_count(count: u64) -> StdResult<u64> {
+    count
+        .checked_sub(1)
+        .ok_or_else(|| StdError::overflow(OverflowError::new(OverflowOperation::Sub, count, 1)))
+}
+
+/// Returns the scheme of an absolute uri, or None if the uri is relative
+pub fn uri_scheme(uri: &str) -> Option<&str> {
+    let (scheme, _) = uri.split_once("://")?;
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+    if valid {
+        Some(scheme)
+    } else {
+        None
+    }
+}
+
+#[cw_serde]
+pub struct MintFeeResponse {
+    /// Fee paid for every minted or claimed nft, minting is free if not set
+    pub fee: Option<Coin>,
+    /// Default recipient of withdrawn fees
+    pub treasury: String,
+}
+
+#[cw_serde]
+pub struct AdminResponse {
+    pub admin: String,
+    /// Admin proposed with `ExecuteMsg::ProposeAdmin` that has not accepted yet
+    pub pending_admin: Option<String>,
+}
+
+impl<'a> IndexList<Offer> for OfferIndexes<'a>{
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Offer>> + '_> {
+        let v: Vec<&dyn Index<Offer>> = vec![&self.recipient];
         Box::new(v.into_iter())
     }
 }
@@ -84,12 +549,111 @@ pub struct NumNftsResponse {
     pub count: u64,
 }
 
+/// Record kept of a destroyed nft, so that its id is never reused
+#[cw_serde]
+pub struct Tombstone {
+    /// Owner of the nft when it was destroyed
+    pub owner: String,
+    pub issuer: String,
+    pub burned_at: Timestamp,
+    pub burned_by: Addr,
+}
+
+/// Change of a nft kept in its history
+#[cw_serde]
+pub struct HistoryEntry {
+    /// Position of the entry in the history of the nft, starting at 0
+    pub seq: u64,
+    /// Same as the action of the `abt_<action>` event of the change
+    pub action: String,
+    /// Address that executed the change
+    pub actor: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// Result of the lookup of one id by `QueryMsg::NftInfos`
+#[cw_serde]
+pub struct NftInfoEntry {
+    pub nft_id: String,
+    /// None if no nft has the id
+    pub info: Option<NftInfo>,
+}
+
+/// Data of the response to `ExecuteMsg::RevokeIssuedBy`
+#[cw_serde]
+pub struct RevokeIssuedByResponse {
+    /// Number of nfts revoked by this page
+    pub revoked: u64,
+    /// Id of the last nft of this page, to pass as `start_after` to revoke the next one.
+    /// None once every nft of the issuer has been walked
+    pub next: Option<String>,
+}
+
+#[cw_serde]
+pub struct NumHoldersResponse {
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+    /// Number of nfts ever issued, as returned by `NumNfts`
+    pub issued: u64,
+    pub equipped: u64,
+    pub unequipped: u64,
+    pub revoked: u64,
+    /// Number of nfts destroyed by their issuer
+    pub burned: u64,
+}
+
+#[cw_serde]
+pub struct BalanceResponse {
+    /// Number of nfts held by the owner, revoked ones included
+    pub balance: u64,
+    pub equipped: u64,
+    pub unequipped: u64,
+    pub revoked: u64,
+}
+
 #[cw_serde]
 pub struct ContractInfoResponse {
     pub name: String,
     pub symbol: String,
+    /// Description of the collection
+    pub description: Option<String>,
+    /// Image or logo of the collection
+    pub image: Option<String>,
+    /// Link to the website of the collection
+    pub external_url: Option<String>,
+    /// Display name of the issuing organisation
+    pub issuer_name: Option<String>,
+    /// Uri of a JSON document with further collection-level metadata
+    pub metadata_uri: Option<String>,
+}
+
+
+#[cw_serde]
+pub struct VerifyMetadataResponse {
+    /// True if the given hash matches the one committed at mint time
+    pub verified: bool,
+}
+
+#[cw_serde]
+pub struct HasBadgeResponse {
+    /// True if the owner holds a nft of the kind that has not been unadmitted and has not expired
+    pub has_badge: bool,
+}
+
+#[cw_serde]
+pub struct HoldsResponse {
+    /// True if the valid nfts of the owner meet the requirement
+    pub holds: bool,
 }
 
+#[cw_serde]
+pub struct IsClaimedResponse {
+    pub is_claimed: bool,
+}
 
 #[cw_serde]
 pub struct OwnerOfResponse {
@@ -99,4 +663,24 @@ pub struct OwnerOfResponse {
 
 pub fn token_owner_idx(_pk: &[u8], d: &NftInfo) -> String {
     d.owner.clone()
-}
\ No newline at end of file
+}
+
+pub fn token_class_idx(_pk: &[u8], d: &NftInfo) -> String {
+    d.class_id.clone().unwrap_or_default()
+}
+
+pub fn token_owner_kind_idx(_pk: &[u8], d: &NftInfo) -> (String, String) {
+    (d.owner.clone(), d.kind.clone().unwrap_or_default())
+}
+
+pub fn token_owner_status_idx(_pk: &[u8], d: &NftInfo) -> (String, String) {
+    (d.owner.clone(), d.status().as_str().to_string())
+}
+
+pub fn token_issuer_idx(_pk: &[u8], d: &NftInfo) -> String {
+    d.issuer.clone()
+}
+
+pub fn offer_recipient_idx(_pk: &[u8], d: &Offer) -> String {
+    d.owner.clone()
+}